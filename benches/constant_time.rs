@@ -0,0 +1,202 @@
+//! A dudect style check that the `_ct` functions take the same time whatever the secret is.
+//!
+//! Each function is timed on a fixed input and on random inputs, picked at random for every
+//! measurement, and Welch's t-test tells whether the two timing distributions differ. As in
+//! dudect, |t| above 4.5 hints at a leak and above 10 means there definitely is one, so this
+//! exits with an error if any `_ct` function goes over 10. The variable time versions are
+//! measured too, for comparison.
+//!
+//! Run with `cargo bench --bench constant_time`, optionally followed by `-- <measurements>`.
+//!
+//! The encoders are given all zeros as the fixed input, which has the most leading zeros.
+//! The length of a base58 string is public though, so the decoders are given strings of the
+//! longest possible length and only their content varies.
+
+use std::{hint::black_box, process::ExitCode, time::Instant};
+
+/* Calls per measurement, to get well above the timer's resolution */
+const BATCH: usize = 16;
+const DEFAULT_MEASUREMENTS: usize = 200_000;
+
+struct Rng(u64);
+
+impl Rng {
+    fn next(&mut self) -> u64 {
+        self.0 ^= self.0 << 13;
+        self.0 ^= self.0 >> 7;
+        self.0 ^= self.0 << 17;
+        self.0
+    }
+
+    fn bytes<const N: usize>(&mut self) -> [u8; N] {
+        core::array::from_fn(|_| self.next() as u8)
+    }
+
+    /// Random bytes at least 2^(8N-2), which always encode to the longest length
+    fn longest<const N: usize>(&mut self) -> [u8; N] {
+        let mut bytes = self.bytes::<N>();
+        bytes[0] |= 0x40;
+        bytes
+    }
+}
+
+/// Welch's t statistic between the timings of the two classes, once the slowest
+/// measurements, which are mostly interrupts and the like, have been cropped.
+fn t_statistic(mut samples: Vec<(bool, u64)>) -> f64 {
+    let mut times: Vec<u64> = samples.iter().map(|&(_, t)| t).collect();
+    times.sort_unstable();
+    let cutoff = times[times.len() * 9 / 10];
+    samples.retain(|&(_, t)| t <= cutoff);
+
+    let stats = |class: bool| {
+        let class: Vec<f64> = samples
+            .iter()
+            .filter(|&&(c, _)| c == class)
+            .map(|&(_, t)| t as f64)
+            .collect();
+        let n = class.len() as f64;
+        let mean = class.iter().sum::<f64>() / n;
+        let var = class.iter().map(|t| (t - mean).powi(2)).sum::<f64>() / (n - 1.0);
+        (n, mean, var)
+    };
+    let (n0, mean0, var0) = stats(false);
+    let (n1, mean1, var1) = stats(true);
+    (mean0 - mean1) / (var0 / n0 + var1 / n1).sqrt()
+}
+
+/// Times `f` on `fixed` and on random inputs from `random`
+fn measure<T: Copy, R>(
+    rng: &mut Rng,
+    measurements: usize,
+    fixed: T,
+    mut random: impl FnMut(&mut Rng) -> T,
+    f: impl Fn(T) -> R,
+) -> f64 {
+    let mut samples = Vec::with_capacity(measurements);
+    for _ in 0..measurements {
+        let class = rng.next() & 1 == 1;
+        let input = if class { random(rng) } else { fixed };
+        let start = Instant::now();
+        for _ in 0..BATCH {
+            black_box(f(black_box(input)));
+        }
+        samples.push((class, start.elapsed().as_nanos() as u64));
+    }
+    t_statistic(samples)
+}
+
+fn main() -> ExitCode {
+    let measurements = std::env::args()
+        .skip(1)
+        .find_map(|arg| arg.parse().ok())
+        .unwrap_or(DEFAULT_MEASUREMENTS);
+    let mut rng = Rng(0x2545_F491_4F6C_DD1D);
+
+    let fixed_32 = [0; 32];
+    let fixed_64 = [0; 64];
+    let encode_32 = |bytes: [u8; 32]| {
+        let mut out = [0; 44];
+        fd_bs58::encode_32_into(&bytes, &mut out);
+        out
+    };
+    let encode_32_ct = |bytes: [u8; 32]| {
+        let mut out = [0; 44];
+        fd_bs58::encode_32_ct_into(&bytes, &mut out);
+        out
+    };
+    let encode_64 = |bytes: [u8; 64]| {
+        let mut out = [0; 88];
+        fd_bs58::encode_64_into(&bytes, &mut out);
+        out
+    };
+    let encode_64_ct = |bytes: [u8; 64]| {
+        let mut out = [0; 88];
+        fd_bs58::encode_64_ct_into(&bytes, &mut out);
+        out
+    };
+    let encoded_32 = |rng: &mut Rng| encode_32(rng.longest());
+    let encoded_64 = |rng: &mut Rng| encode_64(rng.longest());
+
+    let results = [
+        (
+            "encode_32_ct",
+            true,
+            measure(&mut rng, measurements, fixed_32, Rng::bytes, encode_32_ct),
+        ),
+        (
+            "encode_64_ct",
+            true,
+            measure(&mut rng, measurements, fixed_64, Rng::bytes, encode_64_ct),
+        ),
+        (
+            "decode_32_ct",
+            true,
+            measure(
+                &mut rng,
+                measurements,
+                encode_32([0x40; 32]),
+                encoded_32,
+                fd_bs58::decode_32_ct,
+            ),
+        ),
+        (
+            "decode_64_ct",
+            true,
+            measure(
+                &mut rng,
+                measurements,
+                encode_64([0x40; 64]),
+                encoded_64,
+                fd_bs58::decode_64_ct,
+            ),
+        ),
+        (
+            "encode_32",
+            false,
+            measure(&mut rng, measurements, fixed_32, Rng::bytes, encode_32),
+        ),
+        (
+            "encode_64",
+            false,
+            measure(&mut rng, measurements, fixed_64, Rng::bytes, encode_64),
+        ),
+        (
+            "decode_32",
+            false,
+            measure(
+                &mut rng,
+                measurements,
+                encode_32([0x40; 32]),
+                encoded_32,
+                fd_bs58::decode_32,
+            ),
+        ),
+        (
+            "decode_64",
+            false,
+            measure(
+                &mut rng,
+                measurements,
+                encode_64([0x40; 64]),
+                encoded_64,
+                fd_bs58::decode_64,
+            ),
+        ),
+    ];
+
+    let mut leaks = false;
+    for (name, constant_time, t) in results {
+        let verdict = match t.abs() {
+            t if t > 10.0 => "definitely not constant time",
+            t if t > 4.5 => "probably not constant time",
+            _ => "no leak detected",
+        };
+        println!("{name:<13} t = {t:>8.2}  {verdict}");
+        leaks |= constant_time && t.abs() > 10.0;
+    }
+    if leaks {
+        ExitCode::FAILURE
+    } else {
+        ExitCode::SUCCESS
+    }
+}
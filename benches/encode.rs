@@ -16,7 +16,11 @@ fn bench_encode_32(c: &mut Criterion) {
         b.iter(|| bs58::encode(black_box(byt)).into(&mut output));
     });
     group.bench_with_input("encode_fd", bytes, |b, byt| {
-        b.iter(|| fd_bs58::encode_32(black_box(*byt)))
+        b.iter(|| fd_bs58::encode_32(black_box(byt)))
+    });
+    group.bench_with_input("encode_fd_noalloc", bytes, |b, byt| {
+        let mut output = [0; 44];
+        b.iter(|| fd_bs58::encode_32_into(black_box(byt), &mut output));
     });
     group.finish();
 }
@@ -40,7 +44,11 @@ fn bench_encode_64(c: &mut Criterion) {
         b.iter(|| bs58::encode(black_box(byt)).into(&mut output));
     });
     group.bench_with_input("encode_fd", bytes, |b, byt| {
-        b.iter(|| fd_bs58::encode_64(black_box(*byt)))
+        b.iter(|| fd_bs58::encode_64(black_box(byt)))
+    });
+    group.bench_with_input("encode_fd_noalloc", bytes, |b, byt| {
+        let mut output = [0; 88];
+        b.iter(|| fd_bs58::encode_64_into(black_box(byt), &mut output));
     });
     group.finish();
 }
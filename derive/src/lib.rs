@@ -0,0 +1,176 @@
+//! `#[derive(Base58)]` for newtypes around `[u8; 32]` or `[u8; 64]`. Use it through the
+//! `derive` feature of `fd_bs58`, which re-exports it as `fd_bs58::Base58`.
+
+use proc_macro::TokenStream;
+use proc_macro2::TokenStream as TokenStream2;
+use quote::quote;
+use syn::{
+    parse_macro_input, spanned::Spanned, Data, DeriveInput, Expr, ExprLit, Fields, Lit, LitStr,
+    Path, Type,
+};
+
+/// Implements `Display`, `FromStr` and `Debug` for a struct with a single `[u8; 32]` or
+/// `[u8; 64]` field, going through the optimized codec for that width. See the documentation
+/// of `fd_bs58::Base58` for the attributes.
+#[proc_macro_derive(Base58, attributes(base58))]
+pub fn derive_base58(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    expand(&input)
+        .unwrap_or_else(syn::Error::into_compile_error)
+        .into()
+}
+
+/// What `#[base58(...)]` asked for
+#[derive(Default)]
+struct Options {
+    alphabet: Option<Path>,
+    truncated_debug: bool,
+    serde: bool,
+}
+
+fn parse_options(input: &DeriveInput) -> syn::Result<Options> {
+    let mut options = Options::default();
+    for attr in input
+        .attrs
+        .iter()
+        .filter(|attr| attr.path().is_ident("base58"))
+    {
+        attr.parse_nested_meta(|meta| {
+            if meta.path.is_ident("alphabet") {
+                options.alphabet = Some(meta.value()?.parse()?);
+            } else if meta.path.is_ident("debug") {
+                let debug: LitStr = meta.value()?.parse()?;
+                options.truncated_debug = match debug.value().as_str() {
+                    "full" => false,
+                    "truncated" => true,
+                    _ => {
+                        return Err(
+                            meta.error("expected `debug = \"full\"` or `debug = \"truncated\"`")
+                        )
+                    }
+                };
+            } else if meta.path.is_ident("serde") {
+                options.serde = true;
+            } else {
+                return Err(meta.error("expected `alphabet`, `debug` or `serde`"));
+            }
+            Ok(())
+        })?;
+    }
+    Ok(options)
+}
+
+/// The byte count of a `[u8; 32]` or `[u8; 64]` type
+fn width(ty: &Type) -> syn::Result<usize> {
+    let error = || {
+        syn::Error::new(
+            ty.span(),
+            "Base58 can only be derived for a `[u8; 32]` or `[u8; 64]` field",
+        )
+    };
+    let Type::Array(array) = ty else {
+        return Err(error());
+    };
+    if !matches!(&*array.elem, Type::Path(path) if path.path.is_ident("u8")) {
+        return Err(error());
+    }
+    match &array.len {
+        Expr::Lit(ExprLit {
+            lit: Lit::Int(len), ..
+        }) => match len.base10_parse()? {
+            width @ (32 | 64) => Ok(width),
+            _ => Err(syn::Error::new(
+                len.span(),
+                "Base58 only supports 32 and 64 byte fields, which have optimized codecs",
+            )),
+        },
+        _ => Err(error()),
+    }
+}
+
+fn expand(input: &DeriveInput) -> syn::Result<TokenStream2> {
+    let options = parse_options(input)?;
+    let name = &input.ident;
+    if !input.generics.params.is_empty() {
+        return Err(syn::Error::new(
+            input.generics.span(),
+            "Base58 can't be derived for generic types",
+        ));
+    }
+
+    let fields = match &input.data {
+        Data::Struct(data) => &data.fields,
+        _ => {
+            return Err(syn::Error::new(
+                input.span(),
+                "Base58 can only be derived for structs",
+            ))
+        }
+    };
+    let field = match fields.iter().collect::<Vec<_>>()[..] {
+        [field] => field,
+        _ => {
+            return Err(syn::Error::new(
+                fields.span(),
+                "Base58 can only be derived for a struct with a single field",
+            ))
+        }
+    };
+    let width = width(&field.ty)?;
+    let (access, construct) = match (&field.ident, fields) {
+        (Some(ident), _) => (quote!(#ident), quote!(#name { #ident: bytes })),
+        (None, Fields::Unnamed(_)) => (quote!(0), quote!(#name(bytes))),
+        (None, _) => unreachable!(),
+    };
+    let alphabet = match &options.alphabet {
+        Some(path) => quote!(::core::option::Option::Some(&#path)),
+        None => quote!(::core::option::Option::None),
+    };
+    let truncated = options.truncated_debug;
+    let name_str = name.to_string();
+
+    let mut expanded = quote! {
+        impl ::core::fmt::Display for #name {
+            fn fmt(&self, f: &mut ::core::fmt::Formatter<'_>) -> ::core::fmt::Result {
+                ::fd_bs58::derive::fmt_display::<#width>(&self.#access, #alphabet, f)
+            }
+        }
+
+        impl ::core::fmt::Debug for #name {
+            fn fmt(&self, f: &mut ::core::fmt::Formatter<'_>) -> ::core::fmt::Result {
+                ::fd_bs58::derive::fmt_debug::<#width>(#name_str, &self.#access, #alphabet, #truncated, f)
+            }
+        }
+
+        impl ::core::str::FromStr for #name {
+            type Err = ::fd_bs58::Error;
+
+            fn from_str(s: &str) -> ::core::result::Result<Self, ::fd_bs58::Error> {
+                ::fd_bs58::derive::from_str::<#width>(s, #alphabet).map(|bytes| #construct)
+            }
+        }
+    };
+
+    if options.serde {
+        expanded.extend(quote! {
+            impl ::fd_bs58::derive::serde::Serialize for #name {
+                fn serialize<S: ::fd_bs58::derive::serde::Serializer>(
+                    &self,
+                    serializer: S,
+                ) -> ::core::result::Result<S::Ok, S::Error> {
+                    ::fd_bs58::derive::serialize::<#width, S>(&self.#access, #alphabet, serializer)
+                }
+            }
+
+            impl<'de> ::fd_bs58::derive::serde::Deserialize<'de> for #name {
+                fn deserialize<D: ::fd_bs58::derive::serde::Deserializer<'de>>(
+                    deserializer: D,
+                ) -> ::core::result::Result<Self, D::Error> {
+                    ::fd_bs58::derive::deserialize::<#width, D>(deserializer, #alphabet)
+                        .map(|bytes| #construct)
+                }
+            }
+        });
+    }
+    Ok(expanded)
+}
@@ -0,0 +1,29 @@
+#![no_main]
+
+use bs58::decode;
+use libfuzzer_sys::fuzz_target;
+
+fuzz_target!(|data: &[u8]| {
+    let fd = fd_bs58::decode(data);
+    let decoded = decode(data).into_vec();
+
+    match (decoded, fd) {
+        (Ok(decoded_result), Ok(fd_result)) => {
+            if decoded_result != fd_result {
+                panic!(
+                    "decode gave different result: {:?}, {:?}",
+                    decoded_result, fd_result
+                );
+            }
+        }
+        (Err(_), Err(_)) => {
+            // good
+        }
+        (decoded, fd) => {
+            panic!(
+                "bs58 and fd_bs58 disagree on success: {:?}, {:?}",
+                decoded, fd
+            );
+        }
+    }
+});
@@ -0,0 +1,16 @@
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+
+fuzz_target!(|data: &[u8]| {
+    let fd = fd_bs58::encode(data);
+    let encoded = bs58::encode(data).into_string();
+    if fd != encoded {
+        panic!("encode gave different result: {:?}, {:?}", encoded, fd);
+    }
+
+    let decoded = fd_bs58::decode(&fd).unwrap();
+    if decoded != data {
+        panic!("decode did not round trip: {:?}, {:?}", data, decoded);
+    }
+});
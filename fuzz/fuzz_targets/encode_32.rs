@@ -3,10 +3,23 @@
 use bs58::encode;
 use libfuzzer_sys::fuzz_target;
 
-fuzz_target!(|data: [u8; 32]| {
-    let correct = encode(data.clone()).into_string();
-    let encoded = fd_bs58::encode_32(data);
-    let decoded = fd_bs58::decode_32(encoded.clone()).unwrap();
+fuzz_target!(|data: &[u8]| {
+    let encoded = fd_bs58::try_encode_32(data);
+
+    if data.len() != 32 {
+        // wrong-length input must be rejected rather than panic
+        if encoded != Err(fd_bs58::Error::InvalidInputLength {
+            expected: 32,
+            actual: data.len(),
+        }) {
+            panic!("try_encode_32 accepted {} bytes: {:?}", data.len(), encoded);
+        }
+        return;
+    }
+
+    let correct = encode(data).into_string();
+    let encoded = encoded.unwrap();
+    let decoded = fd_bs58::decode_32(&encoded).unwrap();
 
     // check encoding matches
     if correct != encoded {
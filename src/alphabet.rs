@@ -0,0 +1,175 @@
+use crate::constants::{BASE58_ALPHABET, BASE58_INVALID_CHAR};
+
+/// A base58 alphabet: the 58 ASCII characters used for the digits 0 to 57, in order,
+/// along with the inverse table used for decoding.
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub struct Alphabet {
+    pub(crate) encode: [u8; 58],
+    pub(crate) decode: [u8; 256],
+}
+
+impl Alphabet {
+    /// The alphabet used by Bitcoin, Solana and most other users of base58.
+    /// This is the one used by every function without a `_with` suffix.
+    pub const BITCOIN: Alphabet = Alphabet::new_unwrap(BASE58_ALPHABET);
+
+    /// The alphabet used by the XRP Ledger.
+    pub const RIPPLE: Alphabet =
+        Alphabet::new_unwrap(b"rpshnaf39wBUDNEGHJKLM4PQRST7VWXYZ2bcdeCg65jkm8oFqi1tuvAxyz");
+
+    /// The alphabet used by Flickr short URLs.
+    pub const FLICKR: Alphabet =
+        Alphabet::new_unwrap(b"123456789abcdefghijkmnopqrstuvwxyzABCDEFGHJKLMNPQRSTUVWXYZ");
+
+    /// Creates an alphabet from 58 distinct ASCII characters, the first one being the
+    /// digit 0 that leading zero bytes are encoded as.
+    ///
+    /// # Examples
+    ///
+    /// ## Basic example
+    ///
+    /// ```rust
+    /// let alphabet = fd_bs58::Alphabet::new(b"123456789ABCDEFGHJKLMNPQRSTUVWXYZabcdefghijkmnopqrstuvwxyz")?;
+    /// assert_eq!(fd_bs58::Alphabet::BITCOIN, alphabet);
+    /// # Ok::<(), fd_bs58::AlphabetError>(())
+    /// ```
+    ///
+    /// ## Errors
+    ///
+    /// ### Duplicate Character
+    ///
+    /// ```rust
+    /// assert_eq!(
+    ///     fd_bs58::AlphabetError::DuplicateCharacter { character: 'a', first: 0, second: 1 },
+    ///     fd_bs58::Alphabet::new(&[b'a'; 58]).unwrap_err());
+    /// ```
+    pub const fn new(chars: &[u8; 58]) -> Result<Alphabet, AlphabetError> {
+        let mut decode = [BASE58_INVALID_CHAR; 256];
+        let mut i = 0;
+        while i < 58 {
+            let c = chars[i];
+            if !c.is_ascii() {
+                return Err(AlphabetError::NonAsciiCharacter { index: i });
+            }
+            if decode[c as usize] != BASE58_INVALID_CHAR {
+                return Err(AlphabetError::DuplicateCharacter {
+                    character: c as char,
+                    first: decode[c as usize] as usize,
+                    second: i,
+                });
+            }
+            decode[c as usize] = i as u8;
+            i += 1;
+        }
+        Ok(Alphabet {
+            encode: *chars,
+            decode,
+        })
+    }
+
+    const fn new_unwrap(chars: &[u8; 58]) -> Alphabet {
+        match Alphabet::new(chars) {
+            Ok(alphabet) => alphabet,
+            Err(_) => panic!("invalid base58 alphabet"),
+        }
+    }
+
+    /// Returns the characters of the alphabet, in digit order.
+    pub const fn as_bytes(&self) -> &[u8; 58] {
+        &self.encode
+    }
+
+    /// Returns the characters of the alphabet, in digit order.
+    pub const fn as_str(&self) -> &str {
+        // SAFETY: every character was checked to be ASCII when the alphabet was created
+        unsafe { core::str::from_utf8_unchecked(&self.encode) }
+    }
+}
+
+impl Default for Alphabet {
+    fn default() -> Alphabet {
+        Alphabet::BITCOIN
+    }
+}
+
+impl core::fmt::Debug for Alphabet {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_tuple("Alphabet").field(&self.as_str()).finish()
+    }
+}
+
+/// The reason [`Alphabet::new`] rejected an alphabet
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AlphabetError {
+    /// The character at `index` is not ASCII
+    NonAsciiCharacter { index: usize },
+    /// `character` appears both at `first` and `second`
+    DuplicateCharacter {
+        character: char,
+        first: usize,
+        second: usize,
+    },
+}
+
+impl core::fmt::Display for AlphabetError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            AlphabetError::NonAsciiCharacter { index } => {
+                write!(f, "alphabet character at index {index} is not ASCII")
+            }
+            AlphabetError::DuplicateCharacter {
+                character,
+                first,
+                second,
+            } => write!(
+                f,
+                "alphabet character {character:?} appears at both index {first} and {second}"
+            ),
+        }
+    }
+}
+
+impl core::error::Error for AlphabetError {}
+
+#[cfg(test)]
+mod tests {
+    use crate::constants::{
+        BASE58_INVALID_CHAR, BASE58_INVERSE, BASE58_INVERSE_TABLE_OFFSET,
+        BASE58_INVERSE_TABLE_SENTINEL,
+    };
+
+    use super::{Alphabet, AlphabetError};
+
+    #[test]
+    fn test_bitcoin_matches_inverse_table() {
+        for c in 0..=255u8 {
+            let expected = if c < BASE58_INVERSE_TABLE_OFFSET
+                || c - BASE58_INVERSE_TABLE_OFFSET >= BASE58_INVERSE_TABLE_SENTINEL
+            {
+                BASE58_INVALID_CHAR
+            } else {
+                BASE58_INVERSE[(c - BASE58_INVERSE_TABLE_OFFSET) as usize]
+            };
+            assert_eq!(Alphabet::BITCOIN.decode[c as usize], expected);
+        }
+    }
+
+    #[test]
+    fn test_new_errors() {
+        let mut chars = *Alphabet::BITCOIN.as_bytes();
+        chars[57] = 0x80;
+        assert_eq!(
+            Alphabet::new(&chars),
+            Err(AlphabetError::NonAsciiCharacter { index: 57 })
+        );
+        chars[57] = b'2';
+        assert_eq!(
+            Alphabet::new(&chars),
+            Err(AlphabetError::DuplicateCharacter {
+                character: '2',
+                first: 1,
+                second: 57
+            })
+        );
+    }
+}
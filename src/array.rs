@@ -0,0 +1,172 @@
+use core::{fmt, str::FromStr};
+
+use crate::{
+    alphabet::Alphabet, decode_fixed::decode_fixed_fast, encode_fixed::encode_fixed_into_fast,
+    fixed::MAX_ENCODED_LEN, scratch::Scratch, Error,
+};
+
+/// `N` bytes that parse from and print as base58, such as a public key or signature.
+///
+/// Parsing goes through [`decode_fixed`](crate::decode_fixed) and printing through
+/// [`encode_fixed_into`](crate::encode_fixed_into), so neither allocates.
+///
+/// # Examples
+///
+/// ## Basic example
+///
+/// ```rust
+/// use fd_bs58::Bs58Bytes32;
+///
+/// let key: Bs58Bytes32 = "XkCriyrNwS3G4rzAXtG5B1nnvb5Ka1JtCku93VqeKAr".parse()?;
+/// assert_eq!([7, 224, 70, 147], key.as_bytes()[..4]);
+/// assert_eq!("XkCriyrNwS3G4rzAXtG5B1nnvb5Ka1JtCku93VqeKAr", key.to_string());
+/// # Ok::<(), fd_bs58::Error>(())
+/// ```
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Base58Array<const N: usize>(pub [u8; N]);
+
+/// 32 bytes that parse from and print as base58, such as a public key
+pub type Bs58Bytes32 = Base58Array<32>;
+
+/// 64 bytes that parse from and print as base58, such as a signature
+pub type Bs58Bytes64 = Base58Array<64>;
+
+impl<const N: usize> Base58Array<N> {
+    /// Wraps the given bytes.
+    pub const fn new(bytes: [u8; N]) -> Self {
+        Base58Array(bytes)
+    }
+
+    /// Returns the wrapped bytes.
+    pub const fn as_bytes(&self) -> &[u8; N] {
+        &self.0
+    }
+
+    /// Returns the wrapped bytes by value.
+    pub const fn to_bytes(self) -> [u8; N] {
+        self.0
+    }
+}
+
+impl<const N: usize> Default for Base58Array<N> {
+    fn default() -> Self {
+        Base58Array([0; N])
+    }
+}
+
+impl<const N: usize> From<[u8; N]> for Base58Array<N> {
+    fn from(bytes: [u8; N]) -> Self {
+        Base58Array(bytes)
+    }
+}
+
+impl<const N: usize> From<Base58Array<N>> for [u8; N] {
+    fn from(array: Base58Array<N>) -> Self {
+        array.0
+    }
+}
+
+impl<const N: usize> AsRef<[u8]> for Base58Array<N> {
+    fn as_ref(&self) -> &[u8] {
+        &self.0
+    }
+}
+
+impl<const N: usize> AsRef<[u8; N]> for Base58Array<N> {
+    fn as_ref(&self) -> &[u8; N] {
+        &self.0
+    }
+}
+
+impl<const N: usize> FromStr for Base58Array<N> {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Error> {
+        decode_fixed_fast(s.as_bytes(), &Alphabet::BITCOIN).map(Base58Array)
+    }
+}
+
+impl<const N: usize> TryFrom<&str> for Base58Array<N> {
+    type Error = Error;
+
+    fn try_from(s: &str) -> Result<Self, Error> {
+        s.parse()
+    }
+}
+
+impl<const N: usize> fmt::Display for Base58Array<N> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut buf = Scratch::new([0; MAX_ENCODED_LEN]);
+        let len = encode_fixed_into_fast(&self.0, &mut buf[..], &Alphabet::BITCOIN);
+        // SAFETY: every byte written by the encoders comes from the ASCII base58 alphabet
+        f.pad(unsafe { core::str::from_utf8_unchecked(&buf[..len]) })
+    }
+}
+
+impl<const N: usize> fmt::Debug for Base58Array<N> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("Base58Array")
+            .field(&format_args!("{self}"))
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::Error;
+
+    use super::{Base58Array, Bs58Bytes32, Bs58Bytes64};
+
+    #[test]
+    fn test_parse_and_display() {
+        let keys = [
+            "XkCriyrNwS3G4rzAXtG5B1nnvb5Ka1JtCku93VqeKAr",
+            "11111111111111111111111111111111",
+            "JEKNVnkbo3jma5nREBBJCDoXFVeKkD56V3xKrvRmWxFG",
+        ];
+        for key in keys {
+            let parsed: Bs58Bytes32 = key.parse().unwrap();
+            assert_eq!(
+                parsed.as_ref() as &[u8],
+                bs58::decode(key).into_vec().unwrap()
+            );
+            assert_eq!(parsed.to_string(), key);
+            assert_eq!(Bs58Bytes32::try_from(key), Ok(parsed));
+        }
+
+        let signature = Bs58Bytes64::from([255; 64]);
+        assert_eq!(signature.to_string().parse::<Bs58Bytes64>(), Ok(signature));
+        assert_eq!(Base58Array([0; 20]).to_string(), "1".repeat(20));
+    }
+
+    #[test]
+    fn test_formatting() {
+        let key = Bs58Bytes32::default();
+        assert_eq!(
+            format!("{key:?}"),
+            "Base58Array(11111111111111111111111111111111)"
+        );
+        assert_eq!(format!("{key:>34}"), format!("  {key}"));
+        assert_eq!(format!("{key:.4}"), "1111");
+    }
+
+    #[test]
+    fn test_errors() {
+        assert_eq!(
+            "l".repeat(32).parse::<Bs58Bytes32>(),
+            Err(Error::InvalidCharacter {
+                character: 'l',
+                index: 0
+            })
+        );
+        assert!(Bs58Bytes32::try_from("1".repeat(31).as_str()).is_err());
+    }
+
+    #[test]
+    fn test_ordering() {
+        let low = Bs58Bytes32::from([0; 32]);
+        let high = Bs58Bytes32::from([1; 32]);
+        assert!(low < high);
+        assert_eq!(<[u8; 32]>::from(high), [1; 32]);
+    }
+}
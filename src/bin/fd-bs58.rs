@@ -0,0 +1,420 @@
+//! `fd-bs58`: converts between base58 and hex, base64, JSON byte arrays (the format of
+//! Solana keypair files) or raw bytes.
+//!
+//! Inputs are taken from the arguments, or from stdin one line at a time, so large files are
+//! streamed. The exit code tells which [`fd_bs58::Error`] stopped the conversion, see
+//! [`exit_code`].
+
+use std::{
+    fmt::Write as _,
+    io::{self, BufRead, BufWriter, Write},
+    process::ExitCode,
+};
+
+use base64::{engine::general_purpose::STANDARD, Engine};
+use clap::{Parser, Subcommand, ValueEnum};
+use fd_bs58::{Alphabet, Error};
+
+const EXIT_CODES: &str = "\
+Exit codes:
+  0   success
+  1   I/O error
+  2   invalid arguments
+  3   input not valid in the --from format
+  10  invalid base58 character
+  11  base58 input too long
+  12  base58 input too short
+  13  decoded value does not fit --bytes
+  14  input is not --bytes long";
+
+#[derive(Parser)]
+#[command(version, about = "Base58 encoding and decoding", after_help = EXIT_CODES)]
+struct Cli {
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Encodes bytes to base58, one line of output per input
+    Encode {
+        /// The format of the inputs
+        #[arg(long, value_enum, default_value_t = Format::Hex)]
+        from: Format,
+        /// Fails unless every input is exactly this many bytes
+        #[arg(long, value_enum)]
+        bytes: Option<Width>,
+        /// The inputs, read from stdin when there are none
+        inputs: Vec<String>,
+    },
+    /// Decodes base58 to bytes, one line of output per input unless writing raw bytes
+    Decode {
+        /// The format of the outputs
+        #[arg(long, value_enum, default_value_t = Format::Hex)]
+        to: Format,
+        /// Fails unless every input decodes to exactly this many bytes
+        #[arg(long, value_enum)]
+        bytes: Option<Width>,
+        /// The inputs, read from stdin one per line when there are none
+        inputs: Vec<String>,
+    },
+}
+
+#[derive(Clone, Copy, PartialEq, ValueEnum)]
+enum Format {
+    /// Hexadecimal, lowercase on output
+    Hex,
+    /// Standard base64 with padding
+    Base64,
+    /// A JSON array of numbers, such as `[1,2,3]`, on a single line
+    JsonArray,
+    /// The bytes themselves. Stdin is read as a whole when encoding, and outputs are
+    /// concatenated when decoding.
+    Raw,
+}
+
+/// The widths with an optimized codec
+#[derive(Clone, Copy, ValueEnum)]
+enum Width {
+    #[value(name = "32")]
+    Bytes32,
+    #[value(name = "64")]
+    Bytes64,
+}
+
+/// Why a conversion stopped
+#[derive(Debug)]
+enum Failure {
+    /// The input at `line` (starting at 1) is not valid base58 or has the wrong length
+    Codec {
+        line: usize,
+        error: Error,
+    },
+    /// The input at `line` is not valid in the `--from` format
+    Format {
+        line: usize,
+        message: String,
+    },
+    Io(io::Error),
+}
+
+impl From<io::Error> for Failure {
+    fn from(error: io::Error) -> Self {
+        Failure::Io(error)
+    }
+}
+
+/// The exit code for each failure, as listed in [`EXIT_CODES`]. 2 is left for usage errors,
+/// which clap reports itself. The checksum and version errors can't happen yet, but get
+/// codes of their own for when the binary supports Base58Check.
+fn exit_code(failure: &Failure) -> u8 {
+    match failure {
+        Failure::Io(_) => 1,
+        Failure::Format { .. } => 3,
+        Failure::Codec { error, .. } => match error {
+            Error::InvalidCharacter { .. } => 10,
+            Error::InputTooLong { .. } => 11,
+            Error::InputTooShort { .. } => 12,
+            Error::InvalidByteAmount => 13,
+            Error::InvalidInputLength { .. } => 14,
+            Error::InvalidChecksum { .. } => 15,
+            Error::InvalidVersion { .. } => 16,
+        },
+    }
+}
+
+fn parse_bytes(input: &[u8], format: Format) -> Result<Vec<u8>, String> {
+    let text = || core::str::from_utf8(input).map_err(|e| e.to_string());
+    match format {
+        Format::Hex => {
+            let text = text()?.trim();
+            if let Some(c) = text.chars().find(|c| !c.is_ascii_hexdigit()) {
+                return Err(format!("invalid hex digit {c:?}"));
+            }
+            if text.len() % 2 != 0 {
+                return Err("hex input has an odd number of digits".into());
+            }
+            Ok(text
+                .as_bytes()
+                .chunks_exact(2)
+                .map(|pair| (hex_digit(pair[0]) << 4) | hex_digit(pair[1]))
+                .collect())
+        }
+        Format::Base64 => STANDARD.decode(text()?.trim()).map_err(|e| e.to_string()),
+        Format::JsonArray => {
+            let text = text()?.trim();
+            let items = text
+                .strip_prefix('[')
+                .and_then(|text| text.strip_suffix(']'))
+                .ok_or("a JSON array must start with '[' and end with ']'")?;
+            if items.trim().is_empty() {
+                return Ok(Vec::new());
+            }
+            items
+                .split(',')
+                .map(|item| {
+                    let item = item.trim();
+                    /* parse() would also take a leading '+' */
+                    item.bytes()
+                        .all(|c| c.is_ascii_digit())
+                        .then(|| item.parse().ok())
+                        .flatten()
+                        .ok_or_else(|| format!("{item:?} is not a byte"))
+                })
+                .collect()
+        }
+        Format::Raw => Ok(input.to_vec()),
+    }
+}
+
+/// The value of an ASCII hex digit, which the caller has checked
+fn hex_digit(c: u8) -> u8 {
+    match c {
+        b'0'..=b'9' => c - b'0',
+        b'a'..=b'f' => c - b'a' + 10,
+        _ => c - b'A' + 10,
+    }
+}
+
+/// Appends `bytes` in the given format, without a newline
+fn format_bytes(bytes: &[u8], format: Format, output: &mut Vec<u8>) {
+    match format {
+        Format::Hex => {
+            let mut text = String::with_capacity(2 * bytes.len());
+            for byte in bytes {
+                write!(text, "{byte:02x}").unwrap();
+            }
+            output.extend_from_slice(text.as_bytes());
+        }
+        Format::Base64 => output.extend_from_slice(STANDARD.encode(bytes).as_bytes()),
+        Format::JsonArray => {
+            let items: Vec<String> = bytes.iter().map(u8::to_string).collect();
+            output.extend_from_slice(format!("[{}]", items.join(",")).as_bytes());
+        }
+        Format::Raw => output.extend_from_slice(bytes),
+    }
+}
+
+/// Encodes with [`fd_bs58::encode_32`] or [`fd_bs58::encode_64`] when the length allows it
+fn encode(bytes: &[u8], width: Option<Width>) -> Result<String, Error> {
+    match (bytes.len(), width) {
+        (32, None | Some(Width::Bytes32)) => Ok(fd_bs58::encode_32(bytes.try_into().unwrap())),
+        (64, None | Some(Width::Bytes64)) => Ok(fd_bs58::encode_64(bytes.try_into().unwrap())),
+        (_, Some(Width::Bytes32)) => fd_bs58::try_encode_32(bytes),
+        (_, Some(Width::Bytes64)) => fd_bs58::try_encode_64(bytes),
+        (_, None) => Ok(fd_bs58::encode(bytes)),
+    }
+}
+
+fn decode(encoded: &[u8], width: Option<Width>) -> Result<Vec<u8>, Error> {
+    match width {
+        Some(Width::Bytes32) => fd_bs58::decode_32(encoded).map(Vec::from),
+        Some(Width::Bytes64) => fd_bs58::decode_64(encoded).map(Vec::from),
+        None => fd_bs58::decode(encoded),
+    }
+}
+
+/// Converts one input, appending the result to `output`
+fn convert(
+    command: &Command,
+    line: usize,
+    input: &[u8],
+    output: &mut Vec<u8>,
+) -> Result<(), Failure> {
+    match command {
+        Command::Encode { from, bytes, .. } => {
+            let decoded =
+                parse_bytes(input, *from).map_err(|message| Failure::Format { line, message })?;
+            let encoded =
+                encode(&decoded, *bytes).map_err(|error| Failure::Codec { line, error })?;
+            output.extend_from_slice(encoded.as_bytes());
+            output.push(b'\n');
+        }
+        Command::Decode { to, bytes, .. } => {
+            let decoded = decode(input.trim_ascii(), *bytes)
+                .map_err(|error| Failure::Codec { line, error })?;
+            format_bytes(&decoded, *to, output);
+            if *to != Format::Raw {
+                output.push(b'\n');
+            }
+        }
+    }
+    Ok(())
+}
+
+fn run(command: &Command, mut stdin: impl BufRead, stdout: impl Write) -> Result<(), Failure> {
+    let mut stdout = BufWriter::new(stdout);
+    let mut output = Vec::new();
+    let (inputs, raw) = match command {
+        Command::Encode { inputs, from, .. } => (inputs, *from == Format::Raw),
+        Command::Decode { inputs, .. } => (inputs, false),
+    };
+
+    if !inputs.is_empty() {
+        for (i, input) in inputs.iter().enumerate() {
+            convert(command, i + 1, input.as_bytes(), &mut output)?;
+            stdout.write_all(&output)?;
+            output.clear();
+        }
+    } else if raw {
+        let mut input = Vec::new();
+        stdin.read_to_end(&mut input)?;
+        convert(command, 1, &input, &mut output)?;
+        stdout.write_all(&output)?;
+    } else {
+        let mut input = Vec::new();
+        let mut line = 0;
+        loop {
+            input.clear();
+            if stdin.read_until(b'\n', &mut input)? == 0 {
+                break;
+            }
+            line += 1;
+            let input = input.strip_suffix(b"\n").unwrap_or(&input);
+            let input = input.strip_suffix(b"\r").unwrap_or(input);
+            convert(command, line, input, &mut output)?;
+            stdout.write_all(&output)?;
+            output.clear();
+        }
+    }
+    stdout.flush()?;
+    Ok(())
+}
+
+fn main() -> ExitCode {
+    let cli = Cli::parse();
+    match run(&cli.command, io::stdin().lock(), io::stdout().lock()) {
+        Ok(()) => ExitCode::SUCCESS,
+        /* Stopped by `| head` and the like */
+        Err(Failure::Io(error)) if error.kind() == io::ErrorKind::BrokenPipe => ExitCode::SUCCESS,
+        Err(failure) => {
+            match &failure {
+                Failure::Codec { line, error } => match error.suggestion(&Alphabet::BITCOIN) {
+                    Some(suggestion) => {
+                        eprintln!("fd-bs58: input {line}: {error}, did you mean {suggestion:?}?")
+                    }
+                    None => eprintln!("fd-bs58: input {line}: {error}"),
+                },
+                Failure::Format { line, message } => eprintln!("fd-bs58: input {line}: {message}"),
+                Failure::Io(error) => eprintln!("fd-bs58: {error}"),
+            }
+            ExitCode::from(exit_code(&failure))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn run_with(args: &[&str], stdin: &[u8]) -> Result<Vec<u8>, Failure> {
+        let cli = Cli::try_parse_from(["fd-bs58"].iter().chain(args)).unwrap();
+        let mut stdout = Vec::new();
+        run(&cli.command, stdin, &mut stdout)?;
+        Ok(stdout)
+    }
+
+    const KEY_HEX: &str = "07e046933c7090fa2e3e8539fc95dc8fed4d15d0bf3d3ace9888816781308d8b";
+    const KEY_B58: &str = "XkCriyrNwS3G4rzAXtG5B1nnvb5Ka1JtCku93VqeKAr";
+
+    #[test]
+    fn test_formats() {
+        let key = fd_bs58::decode_32(KEY_B58).unwrap();
+        for format in [Format::Hex, Format::Base64, Format::JsonArray, Format::Raw] {
+            let mut formatted = Vec::new();
+            format_bytes(&key, format, &mut formatted);
+            assert_eq!(Ok(key.to_vec()), parse_bytes(&formatted, format));
+        }
+
+        let mut json = Vec::new();
+        format_bytes(&[0, 1, 255], Format::JsonArray, &mut json);
+        assert_eq!(b"[0,1,255]", &json[..]);
+        assert_eq!(
+            Ok(vec![0, 1, 255]),
+            parse_bytes(b" [0, 1,\t255] ", Format::JsonArray)
+        );
+        assert_eq!(Ok(vec![]), parse_bytes(b"[]", Format::JsonArray));
+        assert!(parse_bytes(b"[256]", Format::JsonArray).is_err());
+        assert!(parse_bytes(b"0, 1", Format::JsonArray).is_err());
+        assert!(parse_bytes(b"abc", Format::Hex).is_err());
+        assert!(parse_bytes(b"zz", Format::Hex).is_err());
+        assert!(parse_bytes(b"!", Format::Base64).is_err());
+
+        assert_eq!(Ok(vec![0xab, 0xcd]), parse_bytes(b"AbcD", Format::Hex));
+        assert_eq!(
+            Err("invalid hex digit 'é'".to_string()),
+            parse_bytes("aéb".as_bytes(), Format::Hex)
+        );
+        assert!(parse_bytes("é1".as_bytes(), Format::Hex).is_err());
+        assert!(parse_bytes(b"+f", Format::Hex).is_err());
+        assert!(parse_bytes(b"-f", Format::Hex).is_err());
+        assert!(parse_bytes(b"[+1]", Format::JsonArray).is_err());
+        assert!(parse_bytes(b"[1, -0]", Format::JsonArray).is_err());
+        assert!(parse_bytes("[١]".as_bytes(), Format::JsonArray).is_err());
+    }
+
+    #[test]
+    fn test_encode_decode() {
+        let output = run_with(&["encode", KEY_HEX], b"").unwrap();
+        assert_eq!(format!("{KEY_B58}\n").as_bytes(), &output[..]);
+
+        let output = run_with(&["decode", KEY_B58, "1112"], b"").unwrap();
+        assert_eq!(format!("{KEY_HEX}\n00000001\n").as_bytes(), &output[..]);
+
+        let output = run_with(
+            &["decode", "--to", "raw"],
+            format!("{KEY_B58}\r\n{KEY_B58}\n").as_bytes(),
+        )
+        .unwrap();
+        assert_eq!([fd_bs58::decode_32(KEY_B58).unwrap(); 2].concat(), output);
+
+        let output = run_with(&["encode", "--from", "raw"], &[0, 0, 1]).unwrap();
+        assert_eq!(b"112\n", &output[..]);
+
+        let output = run_with(&["encode", "--from", "json-array"], b"[0,0,1]\n[]\n[58]\n").unwrap();
+        assert_eq!(b"112\n\n21\n", &output[..]);
+    }
+
+    #[test]
+    fn test_failures() {
+        let failure =
+            run_with(&["decode"], format!("{KEY_B58}\n{KEY_B58}0\n").as_bytes()).unwrap_err();
+        assert!(matches!(
+            failure,
+            Failure::Codec {
+                line: 2,
+                error: Error::InvalidCharacter {
+                    character: '0',
+                    index: 43
+                }
+            }
+        ));
+        assert_eq!(10, exit_code(&failure));
+
+        let failure = run_with(&["decode", "--bytes", "64", KEY_B58], b"").unwrap_err();
+        assert_eq!(12, exit_code(&failure));
+
+        let failure = run_with(&["encode", "--bytes", "32", "00"], b"").unwrap_err();
+        assert!(matches!(
+            failure,
+            Failure::Codec {
+                line: 1,
+                error: Error::InvalidInputLength {
+                    expected: 32,
+                    actual: 1
+                }
+            }
+        ));
+        assert_eq!(14, exit_code(&failure));
+
+        let failure = run_with(&["encode"], b"00\n0g\n").unwrap_err();
+        assert!(matches!(failure, Failure::Format { line: 2, .. }));
+        assert_eq!(3, exit_code(&failure));
+
+        let failure = run_with(&["encode", "aéb"], b"").unwrap_err();
+        assert!(matches!(failure, Failure::Format { line: 1, .. }));
+        assert_eq!(3, exit_code(&failure));
+
+        assert!(Cli::try_parse_from(["fd-bs58", "decode", "--bytes", "33"]).is_err());
+    }
+}
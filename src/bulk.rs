@@ -0,0 +1,491 @@
+//! Streaming conversion of large dumps of keys or signatures, between newline separated
+//! base58 and back to back fixed size records.
+//!
+//! The input is read a chunk at a time and every chunk goes through the batch codecs, such as
+//! [`decode_32_batch`](crate::decode_32_batch), so memory use stays bounded whatever the size
+//! of the input. Lines longer than a valid encoding are rejected without being kept in
+//! memory. With the `rayon` feature the `_par` variants also split each chunk across the
+//! rayon thread pool. The output is written in large blocks, so there is no need to wrap it in
+//! a [`BufWriter`](std::io::BufWriter).
+//!
+//! # Examples
+//!
+//! ## Basic example
+//!
+//! ```rust
+//! let lines = "11111111111111111111111111111111\nXkCriyrNwS3G4rzAXtG5B1nnvb5Ka1JtCku93VqeKAr\n";
+//! let mut records = Vec::new();
+//! assert_eq!(2, fd_bs58::bulk::decode_32_lines(lines.as_bytes(), &mut records)?);
+//! assert_eq!(64, records.len());
+//!
+//! let mut encoded = Vec::new();
+//! assert_eq!(2, fd_bs58::bulk::encode_32_records(&records[..], &mut encoded)?);
+//! assert_eq!(lines.as_bytes(), encoded);
+//! # Ok::<(), fd_bs58::bulk::Error>(())
+//! ```
+
+use std::{
+    io::{self, BufRead, Read, Write},
+    ops::Range,
+};
+
+use crate::{
+    constants::{BASE58_ENCODED_32_LEN, BASE58_ENCODED_64_LEN, BYTE_COUNT_32, BYTE_COUNT_64},
+    decode_batch::decode_fixed_batch,
+    encode_batch::encode_fixed_batch,
+    fixed::Fixed,
+};
+
+/// Lines or records handed to the batch codecs at once
+const CHUNK_LEN: usize = 4096;
+
+/// Chunks read at once by the `_par` variants, to give every thread some work
+#[cfg(feature = "rayon")]
+const PAR_CHUNKS: usize = 64;
+
+/// Why a bulk conversion stopped. Everything before the failing line or record has been
+/// written to the output.
+#[derive(Debug)]
+pub enum Error {
+    /// Reading the input or writing the output failed
+    Io(io::Error),
+    /// Line `line`, counting from 1, doesn't decode to the expected number of bytes
+    Decode { line: u64, error: crate::Error },
+    /// The input ends with `len` bytes, which is less than a whole record
+    PartialRecord { len: usize },
+}
+
+impl From<io::Error> for Error {
+    fn from(error: io::Error) -> Self {
+        Error::Io(error)
+    }
+}
+
+impl core::fmt::Display for Error {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            Error::Io(error) => error.fmt(f),
+            Error::Decode { line, error } => write!(f, "line {line}: {error}"),
+            Error::PartialRecord { len } => {
+                write!(f, "the input ends with a partial record of {len} bytes")
+            }
+        }
+    }
+}
+
+impl std::error::Error for Error {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Error::Io(error) => Some(error),
+            Error::Decode { error, .. } => Some(error),
+            Error::PartialRecord { .. } => None,
+        }
+    }
+}
+
+/// Decodes every line of `input`, a 32 byte value in base58, and writes the decoded values
+/// back to back to `output`. Lines end with `\n` or `\r\n`. Returns the number of lines.
+///
+/// # Examples
+///
+/// ## Errors
+///
+/// ```rust
+/// let lines = "11111111111111111111111111111111\nl\n";
+/// let mut records = Vec::new();
+/// let error = fd_bs58::bulk::decode_32_lines(lines.as_bytes(), &mut records).unwrap_err();
+/// assert_eq!("line 2: invalid base58 character 'l' at index 0", error.to_string());
+/// assert_eq!([0; 32][..], records);
+/// ```
+pub fn decode_32_lines<R: BufRead, W: Write>(input: R, output: W) -> Result<u64, Error> {
+    decode_lines::<BYTE_COUNT_32>(input, output, 1, false)
+}
+
+/// Decodes every line of `input`, a 64 byte value in base58, and writes the decoded values
+/// back to back to `output`. Lines end with `\n` or `\r\n`. Returns the number of lines.
+pub fn decode_64_lines<R: BufRead, W: Write>(input: R, output: W) -> Result<u64, Error> {
+    decode_lines::<BYTE_COUNT_64>(input, output, 1, false)
+}
+
+/// Encodes every 32 byte record of `input` and writes them to `output` as base58, one per
+/// line. Returns the number of records.
+pub fn encode_32_records<R: Read, W: Write>(input: R, output: W) -> Result<u64, Error> {
+    encode_records::<BYTE_COUNT_32, { BASE58_ENCODED_32_LEN as usize }>(input, output, 1, false)
+}
+
+/// Encodes every 64 byte record of `input` and writes them to `output` as base58, one per
+/// line. Returns the number of records.
+pub fn encode_64_records<R: Read, W: Write>(input: R, output: W) -> Result<u64, Error> {
+    encode_records::<BYTE_COUNT_64, { BASE58_ENCODED_64_LEN as usize }>(input, output, 1, false)
+}
+
+/// [`decode_32_lines`] spread across the rayon thread pool. The output is the same.
+#[cfg(feature = "rayon")]
+pub fn decode_32_lines_par<R: BufRead, W: Write>(input: R, output: W) -> Result<u64, Error> {
+    decode_lines::<BYTE_COUNT_32>(input, output, PAR_CHUNKS, true)
+}
+
+/// [`decode_64_lines`] spread across the rayon thread pool. The output is the same.
+#[cfg(feature = "rayon")]
+pub fn decode_64_lines_par<R: BufRead, W: Write>(input: R, output: W) -> Result<u64, Error> {
+    decode_lines::<BYTE_COUNT_64>(input, output, PAR_CHUNKS, true)
+}
+
+/// [`encode_32_records`] spread across the rayon thread pool. The output is the same.
+#[cfg(feature = "rayon")]
+pub fn encode_32_records_par<R: Read, W: Write>(input: R, output: W) -> Result<u64, Error> {
+    encode_records::<BYTE_COUNT_32, { BASE58_ENCODED_32_LEN as usize }>(
+        input, output, PAR_CHUNKS, true,
+    )
+}
+
+/// [`encode_64_records`] spread across the rayon thread pool. The output is the same.
+#[cfg(feature = "rayon")]
+pub fn encode_64_records_par<R: Read, W: Write>(input: R, output: W) -> Result<u64, Error> {
+    encode_records::<BYTE_COUNT_64, { BASE58_ENCODED_64_LEN as usize }>(
+        input, output, PAR_CHUNKS, true,
+    )
+}
+
+/// What a chunk converted to: the output up to the first failure, and the failure with its
+/// index in the chunk
+struct Converted {
+    output: Vec<u8>,
+    error: Option<(usize, crate::Error)>,
+}
+
+/// Converts every CHUNK_LEN items with `convert`, on the rayon thread pool if `parallel`
+fn convert_chunks<T: Sync>(
+    items: &[T],
+    parallel: bool,
+    convert: impl Fn(&[T]) -> Converted + Sync + Send,
+) -> Vec<Converted> {
+    #[cfg(feature = "rayon")]
+    if parallel {
+        use rayon::prelude::*;
+        return items.par_chunks(CHUNK_LEN).map(convert).collect();
+    }
+    let _ = parallel;
+    items.chunks(CHUNK_LEN).map(convert).collect()
+}
+
+/// Why [`read_lines`] returned
+enum Stop {
+    /// `max` lines were read
+    Full,
+    /// The input ended
+    End,
+    /// The next line is `len` bytes long without its terminator, more than `max_len`
+    TooLong { len: usize },
+}
+
+/// Reads up to `max` lines into `buf`, storing where each starts and ends without the line
+/// terminator. At most `max_len` bytes of a line are kept, a longer one stops the reading.
+fn read_lines(
+    input: &mut impl BufRead,
+    buf: &mut Vec<u8>,
+    lines: &mut Vec<Range<usize>>,
+    max: usize,
+    max_len: usize,
+) -> io::Result<Stop> {
+    buf.clear();
+    lines.clear();
+    while lines.len() < max {
+        let start = buf.len();
+        /* Room for a \r\n after the longest line */
+        let read = input
+            .by_ref()
+            .take(max_len as u64 + 2)
+            .read_until(b'\n', buf)?;
+        if read == 0 {
+            return Ok(Stop::End);
+        }
+        let mut end = buf.len();
+        if buf[end - 1] == b'\n' {
+            end -= 1;
+            if end > start && buf[end - 1] == b'\r' {
+                end -= 1;
+            }
+        } else if read > max_len + 1 {
+            let len = line_len(input, read, buf[end - 1])?;
+            return Ok(Stop::TooLong { len });
+        }
+        lines.push(start..end);
+    }
+    Ok(Stop::Full)
+}
+
+/// Skips the rest of a line which starts with `read` bytes ending with `last`, and returns
+/// its length without the terminator
+fn line_len(input: &mut impl BufRead, mut read: usize, mut last: u8) -> io::Result<usize> {
+    loop {
+        let available = input.fill_buf()?;
+        let Some(&byte) = available.last() else {
+            return Ok(read);
+        };
+        if let Some(i) = available.iter().position(|&c| c == b'\n') {
+            let before = if i == 0 { last } else { available[i - 1] };
+            input.consume(i + 1);
+            return Ok(read + i - usize::from(before == b'\r'));
+        }
+        let len = available.len();
+        input.consume(len);
+        read += len;
+        last = byte;
+    }
+}
+
+fn decode_lines<const N: usize>(
+    mut input: impl BufRead,
+    mut output: impl Write,
+    chunks: usize,
+    parallel: bool,
+) -> Result<u64, Error> {
+    let () = Fixed::<N>::VALID;
+    let mut buf = Vec::new();
+    let mut lines = Vec::new();
+    let mut count = 0;
+    loop {
+        let stop = read_lines(
+            &mut input,
+            &mut buf,
+            &mut lines,
+            chunks * CHUNK_LEN,
+            Fixed::<N>::ENCODED_LEN,
+        )?;
+        let inputs: Vec<&[u8]> = lines.iter().map(|line| &buf[line.clone()]).collect();
+        let converted = convert_chunks(&inputs, parallel, |inputs| {
+            let mut outputs = vec![[0; N]; inputs.len()];
+            let results = decode_fixed_batch(inputs, &mut outputs);
+            let error = results
+                .into_iter()
+                .enumerate()
+                .find_map(|(i, result)| Some((i, result.err()?)));
+            let ok = error.as_ref().map_or(inputs.len(), |&(i, _)| i);
+            Converted {
+                output: outputs[..ok].concat(),
+                error,
+            }
+        });
+        for (i, converted) in converted.into_iter().enumerate() {
+            output.write_all(&converted.output)?;
+            if let Some((index, error)) = converted.error {
+                let line = count + (i * CHUNK_LEN + index) as u64 + 1;
+                output.flush()?;
+                return Err(Error::Decode { line, error });
+            }
+        }
+        count += lines.len() as u64;
+        match stop {
+            Stop::Full => {}
+            Stop::End => {
+                output.flush()?;
+                return Ok(count);
+            }
+            Stop::TooLong { len } => {
+                output.flush()?;
+                let error = crate::Error::InputTooLong {
+                    max: Fixed::<N>::ENCODED_LEN,
+                    actual: len,
+                };
+                return Err(Error::Decode {
+                    line: count + 1,
+                    error,
+                });
+            }
+        }
+    }
+}
+
+/// Fills `buf` from `input` unless the input ends first. Returns the number of bytes read.
+fn read_full(input: &mut impl Read, buf: &mut [u8]) -> io::Result<usize> {
+    let mut len = 0;
+    while len < buf.len() {
+        match input.read(&mut buf[len..]) {
+            Ok(0) => break,
+            Ok(read) => len += read,
+            Err(e) if e.kind() == io::ErrorKind::Interrupted => {}
+            Err(e) => return Err(e),
+        }
+    }
+    Ok(len)
+}
+
+fn encode_records<const N: usize, const L: usize>(
+    mut input: impl Read,
+    mut output: impl Write,
+    chunks: usize,
+    parallel: bool,
+) -> Result<u64, Error> {
+    let () = Fixed::<N>::VALID;
+    let mut records = vec![[0; N]; chunks * CHUNK_LEN];
+    let mut count = 0;
+    loop {
+        let len = read_full(&mut input, records.as_flattened_mut())?;
+        let whole = len / N;
+        let converted = convert_chunks(&records[..whole], parallel, |inputs| {
+            let mut outputs = vec![[0; L]; inputs.len()];
+            let mut lens = vec![0; inputs.len()];
+            encode_fixed_batch(inputs, &mut outputs, &mut lens);
+            let mut output = Vec::with_capacity(inputs.len() * (L + 1));
+            for (encoded, &len) in outputs.iter().zip(&lens) {
+                output.extend_from_slice(&encoded[..len as usize]);
+                output.push(b'\n');
+            }
+            Converted {
+                output,
+                error: None,
+            }
+        });
+        for converted in converted {
+            output.write_all(&converted.output)?;
+        }
+        count += whole as u64;
+        if len < records.len() * N {
+            output.flush()?;
+            if len % N != 0 {
+                return Err(Error::PartialRecord { len: len % N });
+            }
+            return Ok(count);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn keys(count: usize) -> Vec<[u8; 32]> {
+        (0..count)
+            .map(|i| core::array::from_fn(|j| if j < i % 5 { 0 } else { (i * 31 + j * 7) as u8 }))
+            .collect()
+    }
+
+    #[test]
+    #[cfg_attr(miri, ignore)]
+    fn test_round_trip() {
+        /* Spans a few chunks with a remainder */
+        let keys = keys(3 * CHUNK_LEN + 5);
+        let records = keys.concat();
+        let lines: String = keys
+            .iter()
+            .map(|key| crate::encode_32(key) + "\n")
+            .collect();
+
+        let mut encoded = Vec::new();
+        assert_eq!(
+            keys.len() as u64,
+            encode_32_records(&records[..], &mut encoded).unwrap()
+        );
+        assert_eq!(lines.as_bytes(), encoded);
+
+        let mut decoded = Vec::new();
+        assert_eq!(
+            keys.len() as u64,
+            decode_32_lines(lines.as_bytes(), &mut decoded).unwrap()
+        );
+        assert_eq!(records, decoded);
+
+        /* The last line doesn't need a terminator, and \r\n works too */
+        let lines = lines.trim_end().replace('\n', "\r\n");
+        let mut decoded = Vec::new();
+        assert_eq!(
+            keys.len() as u64,
+            decode_32_lines(lines.as_bytes(), &mut decoded).unwrap()
+        );
+        assert_eq!(records, decoded);
+
+        let signatures: Vec<[u8; 64]> = keys
+            .chunks_exact(2)
+            .map(|pair| core::array::from_fn(|i| pair[i / 32][i % 32]))
+            .collect();
+        let mut encoded = Vec::new();
+        encode_64_records(&keys.concat()[..signatures.len() * 64], &mut encoded).unwrap();
+        let mut decoded = Vec::new();
+        assert_eq!(
+            signatures.len() as u64,
+            decode_64_lines(&encoded[..], &mut decoded).unwrap()
+        );
+        assert_eq!(signatures.concat(), decoded);
+    }
+
+    #[cfg(feature = "rayon")]
+    #[test]
+    #[cfg_attr(miri, ignore)]
+    fn test_par_matches() {
+        let records = keys(PAR_CHUNKS * CHUNK_LEN + 3 * CHUNK_LEN + 3).concat();
+        let mut encoded = Vec::new();
+        let mut encoded_par = Vec::new();
+        encode_32_records(&records[..], &mut encoded).unwrap();
+        encode_32_records_par(&records[..], &mut encoded_par).unwrap();
+        assert_eq!(encoded, encoded_par);
+
+        let mut decoded_par = Vec::new();
+        decode_32_lines_par(&encoded[..], &mut decoded_par).unwrap();
+        assert_eq!(records, decoded_par);
+
+        /* The error is reported at the right line, after everything before it */
+        let line = PAR_CHUNKS * CHUNK_LEN + 2 * CHUNK_LEN + 17;
+        let mut lines: Vec<&[u8]> = encoded.split(|&c| c == b'\n').collect();
+        lines[line - 1] = b"0";
+        let mut decoded_par = Vec::new();
+        let error = decode_32_lines_par(&lines.join(&b'\n')[..], &mut decoded_par).unwrap_err();
+        assert!(matches!(error, Error::Decode { line: l, .. } if l == line as u64));
+        assert_eq!(records[..(line - 1) * 32], decoded_par);
+    }
+
+    #[test]
+    fn test_errors() {
+        let mut decoded = Vec::new();
+        let error = decode_32_lines(&b"11111111111111111111111111111111\n\n"[..], &mut decoded)
+            .unwrap_err();
+        assert!(matches!(
+            error,
+            Error::Decode {
+                line: 2,
+                error: crate::Error::InputTooShort { min: 32, actual: 0 }
+            }
+        ));
+        assert_eq!([0; 32][..], decoded);
+
+        let mut decoded = Vec::new();
+        let error =
+            decode_64_lines(&b"11111111111111111111111111111111\n"[..], &mut decoded).unwrap_err();
+        assert!(matches!(error, Error::Decode { line: 1, .. }));
+        assert!(decoded.is_empty());
+
+        let mut encoded = Vec::new();
+        let error = encode_32_records(&[0; 70][..], &mut encoded).unwrap_err();
+        assert!(matches!(error, Error::PartialRecord { len: 6 }));
+        assert_eq!(b"11111111111111111111111111111111\n".repeat(2), encoded);
+
+        /* Overlong lines aren't kept in memory, but their length is reported */
+        for (line, len) in [
+            (b"1".repeat(100_000), 100_000),
+            (b"2".repeat(46), 46),
+            (b"3".repeat(45), 45),
+        ] {
+            /* Followed by another line, or at the end of the input */
+            for rest in [&b"\n1"[..], b"\r\n1", b"\r", b""] {
+                let lines = [&b"11111111111111111111111111111111\n"[..], &line, rest].concat();
+                let mut decoded = Vec::new();
+                let error = decode_32_lines(&lines[..], &mut decoded).unwrap_err();
+                let actual = len + usize::from(rest == b"\r");
+                assert!(
+                    matches!(
+                        error,
+                        Error::Decode {
+                            line: 2,
+                            error: crate::Error::InputTooLong { max: 44, actual: a }
+                        } if a == actual
+                    ),
+                    "{error:?}"
+                );
+                assert_eq!([0; 32][..], decoded);
+            }
+        }
+
+        assert_eq!(0, decode_32_lines(&b""[..], &mut Vec::new()).unwrap());
+        assert_eq!(0, encode_64_records(&b""[..], &mut Vec::new()).unwrap());
+    }
+}
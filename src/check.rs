@@ -0,0 +1,161 @@
+use alloc::{string::String, vec::Vec};
+
+use crate::{
+    alphabet::Alphabet,
+    checksum::{ChecksumBytes, ChecksumScheme, DoubleSha256},
+    decode::decode,
+    decode_fixed::decode_fixed,
+    encode::encode,
+    encode_fixed::encode_fixed,
+    fixed::Fixed,
+    Error,
+};
+
+/// Length of the double SHA-256 checksum appended to the payload
+pub const CHECKSUM_LEN: usize = DoubleSha256::LEN;
+
+fn checksum(data: &[u8]) -> [u8; CHECKSUM_LEN] {
+    let mut out = [0; CHECKSUM_LEN];
+    DoubleSha256::checksum(data, &mut out);
+    out
+}
+
+// The common Base58Check sizes have fixed width fast paths:
+//   25 bytes: P2PKH/P2SH addresses (version + hash160 + checksum)
+//   37 bytes: uncompressed WIF private keys (version + key + checksum)
+//   38 bytes: compressed WIF private keys (version + key + 0x01 + checksum)
+
+pub(crate) fn encode_check(version: u8, payload: &[u8]) -> String {
+    let mut data: Vec<u8> = Vec::with_capacity(1 + payload.len() + CHECKSUM_LEN);
+    data.push(version);
+    data.extend_from_slice(payload);
+    let checksum = checksum(&data);
+    data.extend_from_slice(&checksum);
+
+    match data.len() {
+        25 => encode_fixed::<25>(data.as_slice().try_into().unwrap(), &Alphabet::BITCOIN),
+        37 => encode_fixed::<37>(data.as_slice().try_into().unwrap(), &Alphabet::BITCOIN),
+        38 => encode_fixed::<38>(data.as_slice().try_into().unwrap(), &Alphabet::BITCOIN),
+        _ => encode(&data, &Alphabet::BITCOIN),
+    }
+}
+
+fn try_decode_fixed<const N: usize>(encoded_bytes: &[u8]) -> Option<Vec<u8>> {
+    if (N..=Fixed::<N>::ENCODED_LEN).contains(&encoded_bytes.len()) {
+        decode_fixed::<N>(encoded_bytes, &Alphabet::BITCOIN)
+            .ok()
+            .map(|bytes| bytes.to_vec())
+    } else {
+        None
+    }
+}
+
+pub(crate) fn decode_check(encoded_bytes: &[u8]) -> Result<(u8, Vec<u8>), Error> {
+    let mut data = match try_decode_fixed::<25>(encoded_bytes)
+        .or_else(|| try_decode_fixed::<37>(encoded_bytes))
+        .or_else(|| try_decode_fixed::<38>(encoded_bytes))
+    {
+        Some(data) => data,
+        None => decode(encoded_bytes, &Alphabet::BITCOIN)?,
+    };
+
+    /* There must be room for at least the version byte and checksum,
+    which takes at least one character per byte */
+    if data.len() < 1 + CHECKSUM_LEN {
+        if encoded_bytes.len() < 1 + CHECKSUM_LEN {
+            return Err(Error::InputTooShort {
+                min: 1 + CHECKSUM_LEN,
+                actual: encoded_bytes.len(),
+            });
+        }
+        return Err(Error::InvalidByteAmount);
+    }
+
+    let split = data.len() - CHECKSUM_LEN;
+    let expected = checksum(&data[..split]);
+    let mut found = [0; CHECKSUM_LEN];
+    found.copy_from_slice(&data[split..]);
+    if expected != found {
+        return Err(Error::InvalidChecksum {
+            expected: ChecksumBytes::new(&expected),
+            found: ChecksumBytes::new(&found),
+        });
+    }
+
+    data.truncate(split);
+    let version = data.remove(0);
+    Ok((version, data))
+}
+
+pub(crate) fn decode_check_with_version(
+    encoded_bytes: &[u8],
+    expected: u8,
+) -> Result<Vec<u8>, Error> {
+    let (found, payload) = decode_check(encoded_bytes)?;
+    if found != expected {
+        return Err(Error::InvalidVersion { expected, found });
+    }
+    Ok(payload)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{checksum::ChecksumBytes, Error};
+
+    use super::{decode_check, decode_check_with_version, encode_check};
+
+    #[test]
+    fn test_check_roundtrip() {
+        for len in 0..100 {
+            let payload: Vec<u8> = (0..len).map(|i| (i * 89 + len) as u8).collect();
+            for version in [0, 5, 0x80, 255] {
+                let encoded = encode_check(version, &payload);
+                assert_eq!(
+                    encoded,
+                    bs58::encode(&payload)
+                        .with_check_version(version)
+                        .into_string()
+                );
+                assert_eq!(
+                    decode_check(encoded.as_bytes()),
+                    Ok((version, payload.clone()))
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_check_errors() {
+        assert_eq!(
+            decode_check(b""),
+            Err(Error::InputTooShort { min: 5, actual: 0 })
+        );
+        assert_eq!(
+            decode_check(b"1111"),
+            Err(Error::InputTooShort { min: 5, actual: 4 })
+        );
+        assert_eq!(decode_check(b"zzzzz"), Err(Error::InvalidByteAmount));
+        assert_eq!(
+            decode_check(b"l"),
+            Err(Error::InvalidCharacter {
+                character: 'l',
+                index: 0
+            })
+        );
+        // last character of the genesis address changed from 'a' to 'b'
+        assert_eq!(
+            decode_check(b"1A1zP1eP5QGefi2DMPTfTL5SLmv7DivfNb"),
+            Err(Error::InvalidChecksum {
+                expected: ChecksumBytes::new(&[0xc2, 0x9b, 0x7d, 0x93]),
+                found: ChecksumBytes::new(&[0xc2, 0x9b, 0x7d, 0x94])
+            })
+        );
+        assert_eq!(
+            decode_check_with_version(b"1A1zP1eP5QGefi2DMPTfTL5SLmv7DivfNa", 5),
+            Err(Error::InvalidVersion {
+                expected: 5,
+                found: 0
+            })
+        );
+    }
+}
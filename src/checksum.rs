@@ -0,0 +1,427 @@
+//! Checked base58 with the checksum scheme of your choice: [`encode_checked`] appends the
+//! checksum of the payload before encoding it, and [`decode_checked`] verifies and strips it.
+//!
+//! Chains disagree on the checksum, so it is a [`ChecksumScheme`] type parameter. These are
+//! built in, each behind its own feature:
+//!
+//! | Scheme               | Used by                 | Feature               |
+//! |----------------------|-------------------------|-----------------------|
+//! | [`DoubleSha256`]     | Bitcoin (Base58Check)   | `checksum-sha256d`    |
+//! | [`Cb58`]             | Avalanche               | `checksum-cb58`       |
+//! | [`Ss58`]             | Substrate / Polkadot    | `checksum-ss58`       |
+//! | [`Ripemd160`]        | EOS                     | `checksum-ripemd160`  |
+//! | [`DoubleBlake256`]   | Decred                  | `checksum-blake256`   |
+//!
+//! Anything else can implement [`ChecksumScheme`] itself. Version bytes, network ids or
+//! other prefixes are part of the payload, as far as this module is concerned.
+//!
+//! [`encode_checked`]: crate::encode_checked
+//! [`decode_checked`]: crate::decode_checked
+//!
+//! # Examples
+//!
+//! ## Basic example
+//!
+//! ```rust
+//! use fd_bs58::checksum::ChecksumScheme;
+//!
+//! /// The first byte of the payload's wrapping sum
+//! struct Sum;
+//!
+//! impl ChecksumScheme for Sum {
+//!     const LEN: usize = 1;
+//!
+//!     fn checksum(data: &[u8], out: &mut [u8]) {
+//!         out[0] = data.iter().fold(0u8, |sum, b| sum.wrapping_add(*b));
+//!     }
+//! }
+//!
+//! # #[cfg(feature = "alloc")] {
+//! let encoded = fd_bs58::encode_checked::<Sum>([1, 2, 3]);
+//! assert_eq!(vec![1, 2, 3], fd_bs58::decode_checked::<Sum>(&encoded)?);
+//! # }
+//! # Ok::<(), fd_bs58::Error>(())
+//! ```
+
+#[cfg(feature = "alloc")]
+use {
+    crate::{alphabet::Alphabet, decode::decode, encode::encode, Error},
+    alloc::{string::String, vec::Vec},
+    core::marker::PhantomData,
+};
+
+/// The longest checksum a [`ChecksumScheme`] can have.  Every built-in scheme uses 4 bytes or
+/// fewer, and [`InvalidChecksum`](crate::Error::InvalidChecksum) keeps both checksums inline,
+/// so a bigger limit would make every [`crate::Error`] bigger.
+pub const MAX_CHECKSUM_LEN: usize = 8;
+
+/// The checksum bytes reported by [`InvalidChecksum`](crate::Error::InvalidChecksum), up to
+/// [`MAX_CHECKSUM_LEN`] of them.
+///
+/// # Examples
+///
+/// ## Basic example
+///
+/// ```rust
+/// use fd_bs58::checksum::ChecksumBytes;
+///
+/// let checksum = ChecksumBytes::new(&[0xc2, 0x9b, 0x7d, 0x93]);
+/// assert_eq!([0xc2, 0x9b, 0x7d, 0x93], checksum.as_bytes());
+/// assert_eq!("[c2, 9b, 7d, 93]", format!("{checksum:02x?}"));
+/// ```
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub struct ChecksumBytes {
+    len: u8,
+    bytes: [u8; MAX_CHECKSUM_LEN],
+}
+
+impl ChecksumBytes {
+    /// Copies `bytes` into a new `ChecksumBytes`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `bytes` is longer than [`MAX_CHECKSUM_LEN`].
+    pub const fn new(bytes: &[u8]) -> ChecksumBytes {
+        assert!(bytes.len() <= MAX_CHECKSUM_LEN, "checksum is too long");
+        let mut checksum = ChecksumBytes {
+            len: bytes.len() as u8,
+            bytes: [0; MAX_CHECKSUM_LEN],
+        };
+        let mut i = 0;
+        while i < bytes.len() {
+            checksum.bytes[i] = bytes[i];
+            i += 1;
+        }
+        checksum
+    }
+
+    /// The checksum, [`ChecksumScheme::LEN`] bytes long
+    pub fn as_bytes(&self) -> &[u8] {
+        &self.bytes[..self.len as usize]
+    }
+}
+
+impl core::fmt::Debug for ChecksumBytes {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        self.as_bytes().fmt(f)
+    }
+}
+
+/// A way to compute the checksum appended to a payload before it is base58 encoded.
+pub trait ChecksumScheme {
+    /// The number of checksum bytes, from 1 to [`MAX_CHECKSUM_LEN`]. Anything else is a
+    /// compile time error when the scheme is used, even where a chain's format allows more.
+    const LEN: usize;
+
+    /// Writes the checksum of `data` to `out`, which is exactly [`LEN`](Self::LEN) bytes.
+    fn checksum(data: &[u8], out: &mut [u8]);
+}
+
+#[cfg(feature = "alloc")]
+struct Checksum<S>(PhantomData<S>);
+
+#[cfg(feature = "alloc")]
+impl<S: ChecksumScheme> Checksum<S> {
+    const VALID: () = assert!(
+        S::LEN >= 1 && S::LEN <= MAX_CHECKSUM_LEN,
+        "ChecksumScheme::LEN must be from 1 to MAX_CHECKSUM_LEN"
+    );
+}
+
+#[cfg(feature = "alloc")]
+pub(crate) fn encode_checked<S: ChecksumScheme>(payload: &[u8]) -> String {
+    let () = Checksum::<S>::VALID;
+    let mut data: Vec<u8> = Vec::with_capacity(payload.len() + S::LEN);
+    data.extend_from_slice(payload);
+    data.resize(payload.len() + S::LEN, 0);
+    S::checksum(payload, &mut data[payload.len()..]);
+    encode(&data, &Alphabet::BITCOIN)
+}
+
+#[cfg(feature = "alloc")]
+pub(crate) fn decode_checked<S: ChecksumScheme>(encoded_bytes: &[u8]) -> Result<Vec<u8>, Error> {
+    let () = Checksum::<S>::VALID;
+    let mut data = decode(encoded_bytes, &Alphabet::BITCOIN)?;
+
+    /* There must be room for the checksum, which takes at least one
+    character per byte */
+    if data.len() < S::LEN {
+        if encoded_bytes.len() < S::LEN {
+            return Err(Error::InputTooShort {
+                min: S::LEN,
+                actual: encoded_bytes.len(),
+            });
+        }
+        return Err(Error::InvalidByteAmount);
+    }
+
+    let split = data.len() - S::LEN;
+    let mut expected = [0; MAX_CHECKSUM_LEN];
+    let expected = &mut expected[..S::LEN];
+    S::checksum(&data[..split], expected);
+    let found = &data[split..];
+    if expected != found {
+        return Err(Error::InvalidChecksum {
+            expected: ChecksumBytes::new(expected),
+            found: ChecksumBytes::new(found),
+        });
+    }
+
+    data.truncate(split);
+    Ok(data)
+}
+
+/// The first 4 bytes of SHA-256(SHA-256(payload)), as in Bitcoin's Base58Check.
+/// [`encode_check`](crate::encode_check) uses it too, with a version byte in front.
+#[cfg(feature = "checksum-sha256d")]
+pub struct DoubleSha256;
+
+#[cfg(feature = "checksum-sha256d")]
+impl ChecksumScheme for DoubleSha256 {
+    const LEN: usize = 4;
+
+    fn checksum(data: &[u8], out: &mut [u8]) {
+        use sha2::{Digest, Sha256};
+
+        out.copy_from_slice(&Sha256::digest(Sha256::digest(data))[..4]);
+    }
+}
+
+/// The last 4 bytes of SHA-256(payload), as in Avalanche's CB58.
+#[cfg(feature = "checksum-cb58")]
+pub struct Cb58;
+
+#[cfg(feature = "checksum-cb58")]
+impl ChecksumScheme for Cb58 {
+    const LEN: usize = 4;
+
+    fn checksum(data: &[u8], out: &mut [u8]) {
+        use sha2::{Digest, Sha256};
+
+        out.copy_from_slice(&Sha256::digest(data)[28..]);
+    }
+}
+
+/// The first 2 bytes of BLAKE2b-512("SS58PRE" || payload), as in Substrate's SS58 for
+/// account ids, where the payload is the address type followed by the 32 byte id.
+#[cfg(feature = "checksum-ss58")]
+pub struct Ss58;
+
+#[cfg(feature = "checksum-ss58")]
+impl ChecksumScheme for Ss58 {
+    const LEN: usize = 2;
+
+    fn checksum(data: &[u8], out: &mut [u8]) {
+        use blake2::{Blake2b512, Digest};
+
+        let hash = Blake2b512::new()
+            .chain_update(b"SS58PRE")
+            .chain_update(data)
+            .finalize();
+        out.copy_from_slice(&hash[..2]);
+    }
+}
+
+/// The first 4 bytes of RIPEMD-160(payload), as in EOS public keys.
+#[cfg(feature = "checksum-ripemd160")]
+pub struct Ripemd160;
+
+#[cfg(feature = "checksum-ripemd160")]
+impl ChecksumScheme for Ripemd160 {
+    const LEN: usize = 4;
+
+    fn checksum(data: &[u8], out: &mut [u8]) {
+        use ripemd::{Digest, Ripemd160};
+
+        out.copy_from_slice(&Ripemd160::digest(data)[..4]);
+    }
+}
+
+/// The first 4 bytes of BLAKE-256(BLAKE-256(payload)), as in Decred addresses.
+#[cfg(feature = "checksum-blake256")]
+pub struct DoubleBlake256;
+
+#[cfg(feature = "checksum-blake256")]
+impl ChecksumScheme for DoubleBlake256 {
+    const LEN: usize = 4;
+
+    fn checksum(data: &[u8], out: &mut [u8]) {
+        use crate::blake256::Blake256;
+        use digest::Digest;
+
+        out.copy_from_slice(&Blake256::digest(Blake256::digest(data))[..4]);
+    }
+}
+
+#[cfg(all(test, feature = "alloc"))]
+mod tests {
+    use crate::Error;
+
+    use super::{decode_checked, encode_checked, ChecksumBytes, ChecksumScheme, MAX_CHECKSUM_LEN};
+
+    struct Xor;
+
+    impl ChecksumScheme for Xor {
+        const LEN: usize = 2;
+
+        fn checksum(data: &[u8], out: &mut [u8]) {
+            out[0] = data.iter().fold(0x5a, |x, b| x ^ b);
+            out[1] = data.len() as u8;
+        }
+    }
+
+    #[test]
+    fn test_checked_roundtrip() {
+        for len in 0..100 {
+            let payload: Vec<u8> = (0..len).map(|i| (i * 89 + len) as u8).collect();
+            let encoded = encode_checked::<Xor>(&payload);
+            let mut expected = payload.clone();
+            let mut checksum = [0; 2];
+            Xor::checksum(&payload, &mut checksum);
+            expected.extend_from_slice(&checksum);
+            assert_eq!(encoded, bs58::encode(&expected).into_string());
+            assert_eq!(decode_checked::<Xor>(encoded.as_bytes()), Ok(payload));
+        }
+    }
+
+    #[test]
+    fn test_checked_errors() {
+        assert_eq!(
+            decode_checked::<Xor>(b""),
+            Err(Error::InputTooShort { min: 2, actual: 0 })
+        );
+        assert_eq!(
+            decode_checked::<Xor>(b"z"),
+            Err(Error::InputTooShort { min: 2, actual: 1 })
+        );
+        // 58 is a single byte
+        assert_eq!(decode_checked::<Xor>(b"21"), Err(Error::InvalidByteAmount));
+        assert_eq!(
+            decode_checked::<Xor>(b"1l"),
+            Err(Error::InvalidCharacter {
+                character: 'l',
+                index: 1
+            })
+        );
+        /* [1, 2, 3] with the checksum of [1, 2, 4] */
+        let encoded = bs58::encode([1, 2, 3, 0x5a ^ 7, 3]).into_string();
+        assert_eq!(
+            decode_checked::<Xor>(encoded.as_bytes()),
+            Err(Error::InvalidChecksum {
+                expected: ChecksumBytes::new(&[0x5a, 3]),
+                found: ChecksumBytes::new(&[0x5a ^ 7, 3])
+            })
+        );
+    }
+
+    /// The payload repeated, as long a checksum as is allowed
+    struct Longest;
+
+    impl ChecksumScheme for Longest {
+        const LEN: usize = MAX_CHECKSUM_LEN;
+
+        fn checksum(data: &[u8], out: &mut [u8]) {
+            for (i, byte) in out.iter_mut().enumerate() {
+                *byte = data.get(i % data.len().max(1)).copied().unwrap_or(0xff);
+            }
+        }
+    }
+
+    #[test]
+    fn test_longest_checksum() {
+        let encoded = encode_checked::<Longest>(&[1, 2, 3]);
+        assert_eq!(
+            decode_checked::<Longest>(encoded.as_bytes()),
+            Ok(vec![1, 2, 3])
+        );
+
+        let encoded = bs58::encode([1, 2, 3, 1, 2, 1, 2, 1, 2, 1, 2]).into_string();
+        assert_eq!(
+            decode_checked::<Longest>(encoded.as_bytes()),
+            Err(Error::InvalidChecksum {
+                expected: ChecksumBytes::new(&[1, 2, 3, 1, 2, 3, 1, 2]),
+                found: ChecksumBytes::new(&[1, 2, 1, 2, 1, 2, 1, 2])
+            })
+        );
+        // both checksums fit in the space the other variants need anyway
+        assert!(core::mem::size_of::<Error>() <= 24);
+    }
+
+    #[cfg(feature = "checksum-sha256d")]
+    #[test]
+    fn test_double_sha256() {
+        use super::DoubleSha256;
+
+        let mut payload = vec![0];
+        payload.extend_from_slice(&[
+            0x62, 0xe9, 0x07, 0xb1, 0x5c, 0xbf, 0x27, 0xd5, 0x42, 0x53, 0x99, 0xeb, 0xf6, 0xf0,
+            0xfb, 0x50, 0xeb, 0xb8, 0x8f, 0x18,
+        ]);
+        let encoded = encode_checked::<DoubleSha256>(&payload);
+        assert_eq!("1A1zP1eP5QGefi2DMPTfTL5SLmv7DivfNa", encoded);
+        assert_eq!(
+            decode_checked::<DoubleSha256>(encoded.as_bytes()),
+            Ok(payload)
+        );
+    }
+
+    #[cfg(feature = "checksum-cb58")]
+    #[test]
+    fn test_cb58() {
+        use super::Cb58;
+
+        let payload: Vec<u8> = (0..32).collect();
+        let encoded = encode_checked::<Cb58>(&payload);
+        assert_eq!("16qJFWMMHFy3xDdLmvUeyc2S6FrWRhJP51HsvDYdz9cWcm5W", encoded);
+        assert_eq!(decode_checked::<Cb58>(encoded.as_bytes()), Ok(payload));
+    }
+
+    #[cfg(feature = "checksum-ss58")]
+    #[test]
+    fn test_ss58() {
+        use super::Ss58;
+
+        /* Alice on the generic Substrate network, address type 42 */
+        let payload =
+            decode_checked::<Ss58>(b"5GrwvaEF5zXb26Fz9rcQpDWS57CtERHpNehXCPcNoHGKutQY").unwrap();
+        assert_eq!(42, payload[0]);
+        assert_eq!(
+            [
+                0xd4, 0x35, 0x93, 0xc7, 0x15, 0xfd, 0xd3, 0x1c, 0x61, 0x14, 0x1a, 0xbd, 0x04, 0xa9,
+                0x9f, 0xd6, 0x82, 0x2c, 0x85, 0x58, 0x85, 0x4c, 0xcd, 0xe3, 0x9a, 0x56, 0x84, 0xe7,
+                0xa5, 0x6d, 0xa2, 0x7d
+            ],
+            payload[1..]
+        );
+        assert!(matches!(
+            decode_checked::<Ss58>(b"5GrwvaEF5zXb26Fz9rcQpDWS57CtERHpNehXCPcNoHGKutQZ"),
+            Err(Error::InvalidChecksum { .. })
+        ));
+    }
+
+    #[cfg(feature = "checksum-ripemd160")]
+    #[test]
+    fn test_ripemd160() {
+        use super::Ripemd160;
+
+        /* EOS6MRyAjQq8ud7hVNYcfnVPJqcVpscN5So8BhtHuGYqET5GDW5CV without the prefix */
+        let encoded = "6MRyAjQq8ud7hVNYcfnVPJqcVpscN5So8BhtHuGYqET5GDW5CV";
+        let payload = decode_checked::<Ripemd160>(encoded.as_bytes()).unwrap();
+        assert_eq!(33, payload.len());
+        assert_eq!([0x02, 0xc0, 0xde, 0xd2], payload[..4]);
+        assert_eq!(encoded, encode_checked::<Ripemd160>(&payload));
+    }
+
+    #[cfg(feature = "checksum-blake256")]
+    #[test]
+    fn test_double_blake256() {
+        use super::DoubleBlake256;
+
+        /* A Decred mainnet P2PKH address, network id 0x073f */
+        let encoded = "DsUZxxoHJSty8DCfwfartwTYbuhmVct7tJu";
+        let payload = decode_checked::<DoubleBlake256>(encoded.as_bytes()).unwrap();
+        assert_eq!(22, payload.len());
+        assert_eq!([0x07, 0x3f], payload[..2]);
+        assert_eq!(encoded, encode_checked::<DoubleBlake256>(&payload));
+    }
+}
@@ -0,0 +1,378 @@
+//! Constant-time versions of the fixed width codec for secret keys.
+//!
+//! Same algorithm as [`crate::encode_fixed`] and [`crate::decode_fixed`], but nothing branches
+//! on or indexes memory with the data: leading zeros are counted with masks, the digits are
+//! shifted into place with a barrel shifter instead of being copied from a data-dependent
+//! offset, and characters are converted by scanning the whole alphabet instead of looking them
+//! up. Divisions are replaced by multiplications since `div` can take a data-dependent number
+//! of cycles. Only the length of the input, which is public, and the compile-time reduction
+//! schedule decide what runs.
+//!
+//! The length of an encoding depends on the value, which is inherent to base58, and errors are
+//! only told apart after all the work is done.
+
+use core::{hint::black_box, mem::MaybeUninit};
+
+use crate::{
+    alphabet::Alphabet,
+    constants::R1_DIV,
+    decode_fixed::{check_max_len, check_min_len},
+    fixed::{Fixed, MAX_BINARY_SZ, MAX_INTERMEDIATE_SZ, MAX_RAW58_SZ},
+    scratch::{init_prefix, Scratch},
+    Error,
+};
+
+#[cfg(feature = "alloc")]
+use {crate::fixed::MAX_ENCODED_LEN, alloc::string::String};
+
+/* floor(2^93 / 58^5) < 2^64, see divrem_r1 */
+const R1_DIV_RECIP: u128 = (1 << 93) / R1_DIV as u128;
+
+/// All ones if `x == y`, 0 otherwise.  `black_box` keeps the compiler from
+/// turning the selects built on these masks back into branches.
+#[inline]
+fn eq_mask(x: u32, y: u32) -> u32 {
+    let d = black_box(x ^ y);
+    /* d | -d has its top bit set unless d is 0 */
+    ((d | d.wrapping_neg()) >> 31).wrapping_sub(1)
+}
+
+/// All ones if `x < y`, 0 otherwise
+#[inline]
+fn lt_mask(x: u64, y: u64) -> u64 {
+    ((u128::from(x).wrapping_sub(u128::from(y)) >> 127) as u64).wrapping_neg()
+}
+
+/// x / 58^5 and x % 58^5.  The reciprocal underestimates x / 58^5 by less
+/// than 2^-29, so the quotient is at most 1 too small, which the remainder
+/// tells us about.
+#[inline]
+fn divrem_r1(x: u64) -> (u64, u64) {
+    let q = ((u128::from(x) * R1_DIV_RECIP) >> 93) as u64;
+    let r = x - q * R1_DIV;
+    let c = !lt_mask(r, R1_DIV) & 1;
+    (q + c, r - c * R1_DIV)
+}
+
+/// x / 58, for x < 2^30.  2369637129 = ceil(2^37 / 58) and the rounding
+/// error stays below 1 for anything that small.
+#[inline]
+fn div58(x: u32) -> u32 {
+    ((u64::from(x) * 2_369_637_129) >> 37) as u32
+}
+
+#[cfg(feature = "alloc")]
+pub(crate) fn encode_fixed_ct<const N: usize>(bytes: &[u8; N], alphabet: &Alphabet) -> String {
+    let mut buf = Scratch::new([0; MAX_ENCODED_LEN]);
+    let len = encode_fixed_ct_into(bytes, &mut buf[..], alphabet);
+    // SAFETY: every byte written by encode_fixed_ct_into comes from the ASCII base58 alphabet
+    String::from(unsafe { core::str::from_utf8_unchecked(&buf[..len]) })
+}
+
+/// Writes the base58 encoding of `bytes` to the start of `out` in constant time and
+/// returns its length. `out` must be at least `Fixed::<N>::ENCODED_LEN` bytes long.
+/// Everything past the returned length, up to `ENCODED_LEN`, is overwritten too.
+#[allow(clippy::needless_range_loop)]
+pub(crate) fn encode_fixed_ct_into<const N: usize>(
+    bytes: &[u8; N],
+    out: &mut [u8],
+    alphabet: &Alphabet,
+) -> usize {
+    let () = Fixed::<N>::VALID;
+    let binary_sz = Fixed::<N>::BINARY_SZ;
+    let intermediate_sz = Fixed::<N>::INTERMEDIATE_SZ;
+    let raw58_sz = Fixed::<N>::RAW58_SZ;
+    let encoded_len = Fixed::<N>::ENCODED_LEN;
+    assert!(out.len() >= encoded_len);
+
+    /* Count leading zeros.  still_0 stays 1 until the first non zero byte. */
+    let mut in_leading_0s = 0;
+    let mut still_0 = 1;
+    for &byte in bytes {
+        still_0 &= eq_mask(u32::from(byte), 0) & 1;
+        in_leading_0s += still_0 as usize;
+    }
+
+    let mut binary_buf = Scratch::new([MaybeUninit::uninit(); MAX_BINARY_SZ]);
+    let binary = init_prefix(&mut binary_buf[..], binary_sz, 0u32);
+    let head = N % 4;
+    for &byte in &bytes[..head] {
+        binary[0] = (binary[0] << 8) | u32::from(byte);
+    }
+    let full_limbs = &mut binary[usize::from(head != 0)..];
+    for (limb, chunk) in full_limbs.iter_mut().zip(bytes[head..].chunks_exact(4)) {
+        *limb = u32::from_be_bytes(chunk.try_into().unwrap());
+    }
+
+    /* See encode_fixed_into for the overflow bounds.  The reduction
+    schedule is fixed at compile time, so it doesn't depend on the data. */
+    let mut intermediate_buf = Scratch::new([MaybeUninit::uninit(); MAX_INTERMEDIATE_SZ]);
+    let intermediate = init_prefix(&mut intermediate_buf[..], intermediate_sz, 0u64);
+    let mut start = 0;
+    while start < binary_sz {
+        let end = Fixed::<N>::ENC_SEGMENT_END[start];
+        for i in start..end {
+            for j in Fixed::<N>::ENC_START..intermediate_sz {
+                intermediate[j] += u64::from(binary[i]) * Fixed::<N>::ENC_TABLE[i][j];
+            }
+        }
+        if end < binary_sz {
+            let reduce = Fixed::<N>::ENC_REDUCE[end];
+            for j in (1..intermediate_sz).rev() {
+                if reduce & (1 << j) != 0 {
+                    let (q, r) = divrem_r1(intermediate[j]);
+                    intermediate[j - 1] += q;
+                    intermediate[j] = r;
+                }
+            }
+        }
+        start = end;
+    }
+
+    for i in (1..intermediate_sz).rev() {
+        let (q, r) = divrem_r1(intermediate[i]);
+        intermediate[i - 1] += q;
+        intermediate[i] = r;
+    }
+
+    let mut raw_base58_buf = Scratch::new([MaybeUninit::uninit(); MAX_RAW58_SZ]);
+    let raw_base58 = init_prefix(&mut raw_base58_buf[..], raw58_sz, 0u8);
+    for i in 0..intermediate_sz {
+        let v = intermediate[i] as u32;
+        let q1 = div58(v);
+        let q2 = div58(q1);
+        let q3 = div58(q2);
+        let q4 = div58(q3);
+        raw_base58[5 * i + 4] = (v - 58 * q1) as u8;
+        raw_base58[5 * i + 3] = (q1 - 58 * q2) as u8;
+        raw_base58[5 * i + 2] = (q2 - 58 * q3) as u8;
+        raw_base58[5 * i + 1] = (q3 - 58 * q4) as u8;
+        raw_base58[5 * i] = q4 as u8;
+    }
+
+    let mut raw_leading_0s = 0;
+    let mut still_0 = 1;
+    for &digit in raw_base58.iter() {
+        still_0 &= eq_mask(u32::from(digit), 0) & 1;
+        raw_leading_0s += still_0 as usize;
+    }
+
+    /* See encode_fixed_into for why this can't underflow.  Rather than
+    reading from raw_base58[skip..], shift everything left by skip one
+    bit of it at a time, always touching every digit. */
+    let skip = raw_leading_0s - in_leading_0s;
+    let mut shift = 1;
+    while shift < raw58_sz {
+        let take = (eq_mask((skip & shift) as u32, 0) as u8) ^ 0xFF;
+        for i in 0..raw58_sz {
+            let shifted = if i + shift < raw58_sz {
+                raw_base58[i + shift]
+            } else {
+                0
+            };
+            raw_base58[i] = (shifted & take) | (raw_base58[i] & !take);
+        }
+        shift <<= 1;
+    }
+
+    for i in 0..encoded_len {
+        let digit = u32::from(raw_base58[i]);
+        let mut c = 0;
+        for (k, &char) in alphabet.encode.iter().enumerate() {
+            c |= u32::from(char) & eq_mask(digit, k as u32);
+        }
+        out[i] = c as u8;
+    }
+
+    raw58_sz - skip
+}
+
+/// Decodes `encoded_bytes` into `N` bytes in constant time, returning the same result as
+/// [`decode_fixed`](crate::decode_fixed::decode_fixed).
+pub(crate) fn decode_fixed_ct<const N: usize>(
+    encoded_bytes: &[u8],
+    alphabet: &Alphabet,
+) -> Result<[u8; N], Error> {
+    let mut out: [u8; N] = [0; N];
+    decode_fixed_ct_into(encoded_bytes, &mut out, alphabet)?;
+    Ok(out)
+}
+
+/// [`decode_fixed_ct`] writing to `out`, so secrets can be decoded straight into memory that
+/// gets wiped.  `out` holds garbage on errors.
+#[allow(clippy::needless_range_loop)]
+pub(crate) fn decode_fixed_ct_into<const N: usize>(
+    encoded_bytes: &[u8],
+    out: &mut [u8; N],
+    alphabet: &Alphabet,
+) -> Result<(), Error> {
+    let () = Fixed::<N>::VALID;
+    check_max_len::<N>(encoded_bytes)?;
+    let binary_sz = Fixed::<N>::BINARY_SZ;
+    let intermediate_sz = Fixed::<N>::INTERMEDIATE_SZ;
+    let raw58_sz = Fixed::<N>::RAW58_SZ;
+
+    /* Validate and convert every character by comparing it against the
+    whole alphabet, keeping track of the first invalid one. */
+    let prepend_0 = raw58_sz - encoded_bytes.len();
+    let mut raw_base58_buf = Scratch::new([MaybeUninit::uninit(); MAX_RAW58_SZ]);
+    let raw_base58 = init_prefix(&mut raw_base58_buf[..], raw58_sz, 0u8);
+    let mut first_invalid = 0;
+    let mut any_invalid = 0;
+    for (index, &c) in encoded_bytes.iter().enumerate() {
+        let mut digit = 0;
+        let mut valid = 0;
+        for (k, &char) in alphabet.encode.iter().enumerate() {
+            let hit = eq_mask(u32::from(c), u32::from(char));
+            digit |= k as u32 & hit;
+            valid |= hit;
+        }
+        raw_base58[prepend_0 + index] = digit as u8;
+        let first = !valid & !any_invalid;
+        first_invalid |= index as u32 & first;
+        any_invalid |= !valid;
+    }
+
+    let mut intermediate_buf = Scratch::new([MaybeUninit::uninit(); MAX_INTERMEDIATE_SZ]);
+    let intermediate = init_prefix(&mut intermediate_buf[..], intermediate_sz, 0u64);
+    for i in 0..intermediate_sz {
+        intermediate[i] = (raw_base58[5 * i] as u64) * 11_316_496
+            + (raw_base58[5 * i + 1] as u64) * 195_112
+            + (raw_base58[5 * i + 2] as u64) * 3_364
+            + (raw_base58[5 * i + 3] as u64) * 58
+            + (raw_base58[5 * i + 4] as u64);
+    }
+
+    /* See decode_raw_base58 for the overflow bounds */
+    let mut binary_buf = Scratch::new([MaybeUninit::uninit(); MAX_BINARY_SZ]);
+    let binary = init_prefix(&mut binary_buf[..], binary_sz, 0u64);
+    let mut start = 0;
+    while start < intermediate_sz {
+        let end = Fixed::<N>::DEC_SEGMENT_END[start];
+        for i in start..end {
+            for j in 0..binary_sz {
+                binary[j] += intermediate[i] * Fixed::<N>::DEC_TABLE[i][j];
+            }
+        }
+        if end < intermediate_sz {
+            let reduce = Fixed::<N>::DEC_REDUCE[end];
+            for j in (1..binary_sz).rev() {
+                if reduce & (1 << j) != 0 {
+                    binary[j - 1] += binary[j] >> 32;
+                    binary[j] &= 0xFFFFFFFF;
+                }
+            }
+        }
+        start = end;
+    }
+
+    for i in (1..binary_sz).rev() {
+        binary[i - 1] += binary[i] >> 32;
+        binary[i] &= 0xFFFFFFFF;
+    }
+
+    let too_big = lt_mask(Fixed::<N>::HEAD_LIMB_MAX, binary[0]);
+
+    let head = N % 4;
+    if head != 0 {
+        out[..head].copy_from_slice(&(binary[0] as u32).to_be_bytes()[4 - head..]);
+    }
+    let full_limbs = &binary[usize::from(head != 0)..binary_sz];
+    for (chunk, limb) in out[head..].chunks_exact_mut(4).zip(full_limbs) {
+        chunk.copy_from_slice(&(*limb as u32).to_be_bytes());
+    }
+
+    /* The number of leading '1's has to match the number of leading 0s */
+    let zero = u32::from(alphabet.encode[0]);
+    let mut in_leading_1s = 0;
+    let mut still_1 = 1;
+    for &c in encoded_bytes {
+        still_1 &= eq_mask(u32::from(c), zero) & 1;
+        in_leading_1s += still_1 as usize;
+    }
+    let mut out_leading_0s = 0;
+    let mut still_0 = 1;
+    for &byte in out.iter() {
+        still_0 &= eq_mask(u32::from(byte), 0) & 1;
+        out_leading_0s += still_0 as usize;
+    }
+
+    /* All the work is done, only the kind of error is left to pick */
+    if any_invalid != 0 {
+        return Err(Error::invalid_character(
+            encoded_bytes,
+            first_invalid as usize,
+        ));
+    }
+    check_min_len::<N>(encoded_bytes)?;
+    if too_big != 0 || in_leading_1s != out_leading_0s {
+        return Err(Error::InvalidByteAmount);
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        alphabet::Alphabet,
+        decode_fixed::decode_fixed,
+        encode_fixed::encode_fixed_into,
+        fixed::{for_each_test_width, test_inputs, Fixed},
+    };
+
+    use super::{decode_fixed_ct, divrem_r1, encode_fixed_ct_into};
+
+    fn check<const N: usize>() {
+        let inputs = test_inputs::<N>(32, 7);
+        for alphabet in [Alphabet::BITCOIN, Alphabet::RIPPLE] {
+            for bytes in &inputs {
+                let mut expected = [0; 256];
+                let expected_len = encode_fixed_into(bytes, &mut expected, &alphabet);
+                let mut out = [0; 256];
+                let len = encode_fixed_ct_into(bytes, &mut out, &alphabet);
+                assert_eq!(&out[..len], &expected[..expected_len]);
+
+                let encoded = &expected[..expected_len];
+                assert_eq!(decode_fixed_ct::<N>(encoded, &alphabet), Ok(*bytes));
+
+                /* Every kind of error, in the same order as decode_fixed */
+                let zero = alphabet.encode[0];
+                let longest = vec![b'z'; Fixed::<N>::ENCODED_LEN];
+                for bad in [
+                    &[][..],
+                    &[zero],
+                    b"l",
+                    &encoded[1..],
+                    &[&[zero][..], encoded].concat(),
+                    &[encoded, b"l"].concat(),
+                    &[b"0", encoded, b"l"].concat(),
+                    &longest,
+                    &[&longest[..], b"z"].concat(),
+                ] {
+                    assert_eq!(
+                        decode_fixed_ct::<N>(bad, &alphabet),
+                        decode_fixed::<N>(bad, &alphabet)
+                    );
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_constant_time_matches_fixed() {
+        for_each_test_width!(check);
+    }
+
+    #[test]
+    fn test_divrem_r1() {
+        let r1 = crate::constants::R1_DIV;
+        for x in [0, 1, r1 - 1, r1, r1 + 1, 2 * r1 - 1, u64::MAX - 1, u64::MAX]
+            .into_iter()
+            .chain((0..64).map(|i| 1 << i))
+            .chain((0..1000u64).map(|i| i.wrapping_mul(0x9E37_79B9_7F4A_7C15)))
+        {
+            assert_eq!(divrem_r1(x), (x / r1, x % r1));
+        }
+    }
+}
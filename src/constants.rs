@@ -12,6 +12,8 @@ pub const BASE58_ENCODED_64_LEN: u64 = 88; /* Computed as ceil(log_58(256^64 - 1
 
 pub const R1_DIV: u64 = 656_356_768; //  58^5
 
+pub const MAX_FIXED_BYTE_COUNT: usize = 128; // Largest N supported by encode_fixed/decode_fixed
+
 pub const BASE58_INVALID_CHAR: u8 = 255;
 pub const BASE58_INVERSE_TABLE_OFFSET: u8 = b'1';
 pub const BASE58_INVERSE_TABLE_SENTINEL: u8 = 1 + b'z' - BASE58_INVERSE_TABLE_OFFSET;
@@ -23,6 +25,9 @@ pub const BASE58_CHARS: &[char] = &[
     'z',
 ];
 
+pub const BASE58_ALPHABET: &[u8; 58] =
+    b"123456789ABCDEFGHJKLMNPQRSTUVWXYZabcdefghijkmnopqrstuvwxyz";
+
 pub const BASE58_INVERSE: [u8; 75] = [
     0, 1, 2, 3, 4, 5, 6, 7, 8, 255, 255, 255, 255, 255, 255, 255, 9, 10, 11, 12, 13, 14, 15, 16,
     255, 17, 18, 19, 20, 21, 255, 22, 23, 24, 25, 26, 27, 28, 29, 30, 31, 32, 255, 255, 255, 255,
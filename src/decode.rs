@@ -0,0 +1,187 @@
+use alloc::vec::Vec;
+
+use crate::{
+    alphabet::Alphabet,
+    constants::{
+        BASE58_ENCODED_32_LEN, BASE58_ENCODED_64_LEN, BASE58_INVALID_CHAR, BYTE_COUNT_32,
+        BYTE_COUNT_64,
+    },
+    decode_fixed::decode_fixed_fast,
+    scratch::Scratch,
+    Error,
+};
+
+pub(crate) fn decode(encoded_bytes: &[u8], alphabet: &Alphabet) -> Result<Vec<u8>, Error> {
+    /* Strings of this length may hold a 32 or 64 byte value.
+    Try the fast path first and fall back if it fails. */
+    let len = encoded_bytes.len();
+    if (BYTE_COUNT_32..=BASE58_ENCODED_32_LEN as usize).contains(&len) {
+        if let Ok(bytes) = decode_fixed_fast::<32>(encoded_bytes, alphabet) {
+            return Ok(bytes.to_vec());
+        }
+    } else if (BYTE_COUNT_64..=BASE58_ENCODED_64_LEN as usize).contains(&len) {
+        if let Ok(bytes) = decode_fixed_fast::<64>(encoded_bytes, alphabet) {
+            return Ok(bytes.to_vec());
+        }
+    }
+
+    /* Validate string and convert to digits */
+    let mut raw_base58: Scratch<Vec<u8>> = Scratch::new(Vec::with_capacity(len));
+    for (index, c) in encoded_bytes.iter().enumerate() {
+        let digit = alphabet.decode[*c as usize];
+        if digit == BASE58_INVALID_CHAR {
+            return Err(Error::invalid_character(encoded_bytes, index));
+        }
+        raw_base58.push(digit);
+    }
+
+    /* Every leading zero digit ('1' in the Bitcoin alphabet)
+    becomes a leading zero byte, the rest is a plain number. */
+    let leading_1s = raw_base58.iter().take_while(|&&d| d == 0).count();
+    let raw_base58 = &raw_base58[leading_1s..];
+
+    /* Feed the number in up to five digits at a time, most significant
+    first, and keep it in base 2^32, least significant limb first:
+      X = sum_i binary[i] * 2^(32*i)
+    binary[i] < 2^32 and the chunk is less than 58^5, so
+    binary[i] * 58^5 + carry fits comfortably in 64 bits. */
+    let mut binary: Scratch<Vec<u32>> =
+        Scratch::new(Vec::with_capacity(raw_base58.len() * 3 / 16 + 1));
+    let head = match raw_base58.len() % 5 {
+        0 => 5.min(raw_base58.len()),
+        head => head,
+    };
+    let (head, rest) = raw_base58.split_at(head);
+    for chunk in core::iter::once(head).chain(rest.chunks_exact(5)) {
+        let mul = 58u64.pow(chunk.len() as u32);
+        let mut carry = chunk.iter().fold(0, |acc, &d| acc * 58 + u64::from(d));
+        for limb in binary.iter_mut() {
+            let v = u64::from(*limb) * mul + carry;
+            *limb = v as u32;
+            carry = v >> 32;
+        }
+        if carry != 0 {
+            binary.push(carry as u32);
+        }
+    }
+
+    /* Write the limbs out big-endian, skipping the zero bytes at the
+    top of the most significant limb. */
+    let mut out: Vec<u8> = Vec::with_capacity(leading_1s + 4 * binary.len());
+    out.resize(leading_1s, 0);
+    for (i, limb) in binary.iter().rev().enumerate() {
+        let limb = limb.to_be_bytes();
+        let skip = if i == 0 {
+            limb.iter().take_while(|&&b| b == 0).count()
+        } else {
+            0
+        };
+        out.extend_from_slice(&limb[skip..]);
+    }
+
+    Ok(out)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{alphabet::Alphabet, Error};
+
+    use super::decode;
+
+    #[test]
+    #[cfg_attr(miri, ignore)]
+    fn test_decode() {
+        let bitcoin = &Alphabet::BITCOIN;
+        assert_eq!(decode(b"", bitcoin), Ok(vec![]));
+        assert_eq!(decode(b"1", bitcoin), Ok(vec![0]));
+        assert_eq!(decode(b"111", bitcoin), Ok(vec![0, 0, 0]));
+        assert_eq!(decode(b"z", bitcoin), Ok(vec![57]));
+        assert_eq!(decode(b"21", bitcoin), Ok(vec![58]));
+        assert_eq!(
+            decode(b"2NEpo7TZRRrLZSi2U", bitcoin),
+            Ok(b"Hello World!".to_vec())
+        );
+        assert_eq!(
+            decode(b"l", bitcoin),
+            Err(Error::InvalidCharacter {
+                character: 'l',
+                index: 0
+            })
+        );
+        assert_eq!(
+            decode(b"1111 ", bitcoin),
+            Err(Error::InvalidCharacter {
+                character: ' ',
+                index: 4
+            })
+        );
+
+        for len in 0..200 {
+            for leading_0s in [0, 1, 5] {
+                let bytes: Vec<u8> = (0..len)
+                    .map(|i| {
+                        if i < leading_0s {
+                            0
+                        } else {
+                            (i * 89 + len) as u8
+                        }
+                    })
+                    .collect();
+                let encoded = bs58::encode(&bytes).into_string();
+                assert_eq!(decode(encoded.as_bytes(), bitcoin), Ok(bytes));
+            }
+            let bytes = vec![255; len];
+            let encoded = bs58::encode(&bytes).into_string();
+            assert_eq!(decode(encoded.as_bytes(), bitcoin), Ok(bytes));
+        }
+    }
+
+    #[test]
+    fn test_decode_fast_path_lengths() {
+        // lengths the 32 and 64 byte decoders are tried on, but which decode to other sizes
+        for encoded in [
+            "1".repeat(40),
+            "z".repeat(44),
+            "1".repeat(70),
+            "z".repeat(88),
+        ] {
+            assert_eq!(
+                decode(encoded.as_bytes(), &Alphabet::BITCOIN),
+                Ok(bs58::decode(&encoded).into_vec().unwrap())
+            );
+        }
+    }
+
+    #[test]
+    fn test_decode_alphabets() {
+        for len in [0, 1, 20, 25, 32, 64, 100] {
+            let bytes: Vec<u8> = (0..len).map(|i| if i < 2 { 0 } else { i as u8 }).collect();
+            let encoded = bs58::encode(&bytes)
+                .with_alphabet(bs58::Alphabet::RIPPLE)
+                .into_string();
+            assert_eq!(
+                decode(encoded.as_bytes(), &Alphabet::RIPPLE),
+                Ok(bytes.clone())
+            );
+            let encoded = bs58::encode(&bytes)
+                .with_alphabet(bs58::Alphabet::FLICKR)
+                .into_string();
+            assert_eq!(decode(encoded.as_bytes(), &Alphabet::FLICKR), Ok(bytes));
+        }
+        // '0' isn't in any of them, 'l' only in the Ripple one
+        assert_eq!(
+            decode(b"0", &Alphabet::RIPPLE),
+            Err(Error::InvalidCharacter {
+                character: '0',
+                index: 0
+            })
+        );
+        assert_eq!(
+            decode(b"l", &Alphabet::FLICKR),
+            Err(Error::InvalidCharacter {
+                character: 'l',
+                index: 0
+            })
+        );
+    }
+}
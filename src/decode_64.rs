@@ -1,136 +1,84 @@
 use crate::{
-    constants::{
-        BASE58_ENCODED_64_LEN, BASE58_INVALID_CHAR, BASE58_INVERSE, BASE58_INVERSE_TABLE_OFFSET,
-        BASE58_INVERSE_TABLE_SENTINEL, BINARY_SZ_64, BYTE_COUNT_64, DEC_TABLE_64,
-        INTERMEDIATE_SZ_64, RAW58_SZ_64,
-    },
+    alphabet::Alphabet,
+    constants::{BINARY_SZ_64, BYTE_COUNT_64, DEC_TABLE_64, INTERMEDIATE_SZ_64},
+    decode_fixed::{decode_binary_const, decode_fixed, intermediate_const},
     Error,
 };
 
+#[cfg(feature = "alloc")]
+use {crate::decode_batch::decode_fixed_batch, alloc::vec::Vec};
+
 pub(crate) fn decode_64<I: AsRef<[u8]>>(input: I) -> Result<[u8; BYTE_COUNT_64], Error> {
-    let encoded_bytes = input.as_ref();
-    if encoded_bytes.len() > BASE58_ENCODED_64_LEN as usize {
-        return Err(Error::InputTooLong);
+    #[cfg(all(target_arch = "x86_64", not(feature = "force-scalar")))]
+    if crate::encode_avx2::is_available() {
+        // SAFETY: AVX2 support was just checked
+        return unsafe { crate::decode_avx2::decode_fixed_avx2(input.as_ref()) };
     }
-
-    /* Validate string and count characters before the nul terminator */
-    let mut char_cnt: u64 = 0;
-    for c in encoded_bytes.iter() {
-        let idx: u64 = if c < &b'1' {
-            u64::MAX
-        } else {
-            (*c as u64) - (BASE58_INVERSE_TABLE_OFFSET as u64)
-        };
-        let idx = std::cmp::min(idx, BASE58_INVERSE_TABLE_SENTINEL as u64);
-
-        if BASE58_INVERSE[idx as usize] == BASE58_INVALID_CHAR {
-            return Err(Error::InvalidCharacter);
-        }
-
-        char_cnt += 1;
+    #[cfg(all(target_arch = "x86_64", not(feature = "force-scalar")))]
+    if crate::decode_sse41::is_available() {
+        // SAFETY: SSE4.1 support was just checked
+        return unsafe { crate::decode_sse41::decode_fixed_sse41(input.as_ref()) };
     }
-
-    /* X = sum_i raw_base58[i] * 58^(RAW58_SZ-1-i) */
-    let mut raw_base58: [u8; RAW58_SZ_64] = [0; RAW58_SZ_64];
-
-    /* Prepend enough 0s to make it exactly RAW58_SZ characters */
-
-    let prepend_0 = RAW58_SZ_64 - char_cnt as usize;
-
-    for j in 0..RAW58_SZ_64 {
-        if j < prepend_0 {
-            raw_base58[j] = 0;
-        } else {
-            raw_base58[j] = BASE58_INVERSE
-                [(encoded_bytes[j - prepend_0] as usize) - BASE58_INVERSE_TABLE_OFFSET as usize];
-        }
+    #[cfg(all(target_arch = "aarch64", not(feature = "force-scalar")))]
+    if crate::encode_neon::is_available() {
+        // SAFETY: NEON support was just checked
+        return unsafe { crate::decode_neon::decode_fixed_neon(input.as_ref()) };
     }
+    decode_fixed(input.as_ref(), &Alphabet::BITCOIN)
+}
 
-    /* Convert to the intermediate format (base 58^5):
-    X = sum_i intermediate[i] * 58^(5*(INTERMEDIATE_SZ-1-i)) */
-
-    let mut intermediate: [u64; INTERMEDIATE_SZ_64] = [0; INTERMEDIATE_SZ_64];
-    for i in 0..INTERMEDIATE_SZ_64 {
-        intermediate[i] = (raw_base58[5 * i] as u64) * 11_316_496
-            + (raw_base58[5 * i + 1] as u64) * 195_112
-            + (raw_base58[5 * i + 2] as u64) * 3_364
-            + (raw_base58[5 * i + 3] as u64) * 58
-            + (raw_base58[5 * i + 4] as u64);
-    }
+pub(crate) fn decode_64_with<I: AsRef<[u8]>>(
+    input: I,
+    alphabet: &Alphabet,
+) -> Result<[u8; BYTE_COUNT_64], Error> {
+    decode_fixed(input.as_ref(), alphabet)
+}
 
-    /* Using the table, convert to overcomplete base 2^32 (terms can be
-    larger than 2^32).  We need to be careful about overflow.
-    For N==32, the largest anything in binary can get is binary[7]:
-    even if intermediate[i]==58^5-1 for all i, then binary[7] < 2^63.
-    For N==64, the largest anything in binary can get is binary[13]:
-    even if intermediate[i]==58^5-1 for all i, then binary[13] <
-    2^63.998.  Hanging in there, just by a thread! */
-
-    let mut binary: [u64; BINARY_SZ_64] = [0; BINARY_SZ_64];
-    for j in 0..BINARY_SZ_64 {
-        let mut acc: u64 = 0;
-        for i in 0..INTERMEDIATE_SZ_64 {
-            acc += intermediate[i] * DEC_TABLE_64[i][j];
+/// [`decode_64`] as a `const fn`, using the precomputed DEC_TABLE_64.  Slower, but usable
+/// for constants.
+pub(crate) const fn decode_64_const(encoded: &str) -> Result<[u8; BYTE_COUNT_64], Error> {
+    let encoded_bytes = encoded.as_bytes();
+    let intermediate = match intermediate_const::<BYTE_COUNT_64, INTERMEDIATE_SZ_64>(encoded_bytes)
+    {
+        Ok(intermediate) => intermediate,
+        Err(e) => return Err(e),
+    };
+
+    /* Same bounds as in decode_fixed: binary[13] < 2^63.998 */
+    let mut binary = [0; BINARY_SZ_64];
+    let mut j = 0;
+    while j < BINARY_SZ_64 {
+        let mut i = 0;
+        while i < INTERMEDIATE_SZ_64 {
+            binary[j] += intermediate[i] * DEC_TABLE_64[i][j];
+            i += 1;
         }
-        binary[j] = acc;
+        j += 1;
     }
 
-    /* Make sure each term is less than 2^32.
-    For N==32, we have plenty of headroom in binary, so overflow is
-    not a concern this time.
-    For N==64, even if we add 2^32 to binary[13], it is still 2^63.998,
-    so this won't overflow. */
-
-    for i in (1..(BINARY_SZ_64)).rev() {
+    let mut i = BINARY_SZ_64 - 1;
+    while i > 0 {
         binary[i - 1] += binary[i] >> 32;
         binary[i] &= 0xFFFFFFFF;
+        i -= 1;
     }
 
-    /* If the largest term is 2^32 or bigger, it means N is larger than
-    what can fit in BYTE_CNT bytes.  This can be triggered, by passing
-    a base58 string of all 'z's for example. */
-
-    if binary[0] > 0xFFFFFFFF {
-        return Err(Error::InvalidByteAmount);
-    }
-
-    let mut out: [u8; BYTE_COUNT_64] = [0; BYTE_COUNT_64];
-    for i in 0..BINARY_SZ_64 {
-        let bytes = (binary[i] as u32).to_be_bytes();
-        out[4 * i] = bytes[0];
-        out[4 * i + 1] = bytes[1];
-        out[4 * i + 2] = bytes[2];
-        out[4 * i + 3] = bytes[3];
-    }
-
-    /* Make sure the encoded version has the same number of leading '1's
-    as the decoded version has leading 0s. */
-
-    let mut leading_zero_cnt: usize = 0;
-    while leading_zero_cnt < BYTE_COUNT_64 {
-        if out[leading_zero_cnt] != 0 {
-            break;
-        }
-        if leading_zero_cnt >= encoded_bytes.len() {
-            return Err(Error::InputTooShort);
-        }
-        if encoded_bytes[leading_zero_cnt] != b'1' {
-            return Err(Error::InputTooShort);
-        }
-        leading_zero_cnt += 1;
-    }
+    decode_binary_const(encoded_bytes, &binary)
+}
 
-    if leading_zero_cnt < encoded_bytes.len() && encoded_bytes[leading_zero_cnt] == b'1' {
-        return Err(Error::InputTooLong);
-    }
-    Ok(out)
+#[cfg(feature = "alloc")]
+pub(crate) fn decode_64_batch<I: AsRef<[u8]>>(
+    inputs: &[I],
+    outputs: &mut [[u8; BYTE_COUNT_64]],
+) -> Vec<Result<(), Error>> {
+    decode_fixed_batch(inputs, outputs)
 }
 
 #[cfg(test)]
 mod tests {
-    use crate::Error;
+    use crate::{alphabet::Alphabet, decode_fixed::decode_fixed, Error};
 
-    use super::decode_64;
+    use super::{decode_64, decode_64_const};
 
     #[test]
     fn test_decode_64() {
@@ -165,7 +113,7 @@ mod tests {
         for key in keys {
             let fd = decode_64(key);
             assert!(fd.is_err());
-            assert!(fd.is_err_and(|x| x == Error::InvalidCharacter));
+            assert!(fd.is_err_and(|x| matches!(x, Error::InvalidCharacter { index: 63, .. })));
         }
     }
 
@@ -186,4 +134,35 @@ mod tests {
             assert!(fd.is_err());
         }
     }
+
+    #[test]
+    fn test_decode_64_const() {
+        const KEY: Result<[u8; 64], Error> = decode_64_const("5eQS44iKV8B4b4gTt4tPZLPSHtD7F78fFDhbHDknsrAE1vUipnDf3pK6h5eZ8CqWqFgZPoYY6XHKUuvyt7BLWHpb");
+        assert_eq!(decode_64("5eQS44iKV8B4b4gTt4tPZLPSHtD7F78fFDhbHDknsrAE1vUipnDf3pK6h5eZ8CqWqFgZPoYY6XHKUuvyt7BLWHpb"), KEY);
+
+        let mut inputs = vec![
+            String::new(),
+            "1111111111111111111111111111111111111111111111111111111111111111".to_string(),
+            "z".repeat(88),
+            "1".repeat(65),
+            "5eQS44iKV8B4b4gTt4tPZLPSHtD7F78fFDhbHDknsrAE1vUipnDf3pK6h5eZ8CqWqFgZPoYY6XHKUuvyt7BLW€".to_string(),
+            "5eQS44iKV8éb4gTt4tPZLPSHtD7F78fFDhbHDknsrAE1vUipnDf3pK6h5eZ8CqWqFgZPoYY6XHKUuvyt7BLWHpb".to_string(),
+        ];
+        /* Every length, with and without leading '1's */
+        for len in 0..=90 {
+            let digits: String = (0..len)
+                .map(|i| Alphabet::BITCOIN.as_bytes()[(i * 37 + len) % 58] as char)
+                .collect();
+            inputs.push(digits.clone());
+            inputs.push(format!("1{digits}"));
+            inputs.push(format!("11{}", &digits[digits.len().min(2)..]));
+        }
+        for input in inputs {
+            assert_eq!(
+                decode_fixed::<64>(input.as_bytes(), &Alphabet::BITCOIN),
+                decode_64_const(&input),
+                "{input}"
+            );
+        }
+    }
 }
@@ -0,0 +1,117 @@
+use core::arch::x86_64::*;
+
+use crate::{
+    alphabet::Alphabet,
+    decode_fixed::{check_max_len, check_min_len, decode_raw_base58},
+    fixed::{Fixed, MAX_RAW58_SZ},
+    scratch::Scratch,
+    Error,
+};
+
+/* Whole vectors covering the longest raw_base58 */
+const RAW_BUF_SZ: usize = MAX_RAW58_SZ.div_ceil(32) * 32;
+
+/* Tables for to_digits, indexed by one nibble of the character.  Each
+row of 16 characters that has any of the alphabet in it, '0' to '?' up to
+'p' to DEL, gets a bit in ROW_BIT.  VALID_IN_ROWS says in which rows each
+low nibble is in the alphabet, and SKIPPED_IN_ROWS in which rows it comes
+after a skipped character ('I' and 'l'), which takes one more off the
+digit.  Otherwise each row is shifted down by OFFSET_PLUS_1[row] - 1. */
+pub(crate) const ROW_BIT: [u8; 16] = [0, 0, 0, 1, 2, 4, 8, 16, 0, 0, 0, 0, 0, 0, 0, 0];
+pub(crate) const VALID_IN_ROWS: [u8; 16] = [
+    0x14, 0x1F, 0x1F, 0x1F, 0x1F, 0x1F, 0x1F, 0x1F, 0x1F, 0x1D, 0x1E, 0x0A, 0x02, 0x0A, 0x0A, 0x08,
+];
+pub(crate) const SKIPPED_IN_ROWS: [u8; 16] = [0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 2, 2, 2, 10, 10, 10];
+pub(crate) const OFFSET_PLUS_1: [u8; 16] = [
+    0, 0, 0, 0x32, 0x39, 0x3B, 0x41, 0x42, 0, 0, 0, 0, 0, 0, 0, 0,
+];
+
+/// `table[nibble]` in every byte
+#[target_feature(enable = "avx2")]
+#[inline]
+unsafe fn lookup(table: &[u8; 16], nibbles: __m256i) -> __m256i {
+    let table = _mm256_broadcastsi128_si256(_mm_loadu_si128(table.as_ptr().cast()));
+    _mm256_shuffle_epi8(table, nibbles)
+}
+
+/// Maps every byte from the Bitcoin alphabet to its digit.  Returns the digits and
+/// a bitmask of the bytes that aren't in the alphabet.  Bytes from 0x80 up fall in
+/// rows without a bit, so they are never valid.
+#[target_feature(enable = "avx2")]
+#[inline]
+unsafe fn to_digits(c: __m256i) -> (__m256i, u32) {
+    let nibble = _mm256_set1_epi8(0x0F);
+    let lo = _mm256_and_si256(c, nibble);
+    let hi = _mm256_and_si256(_mm256_srli_epi16::<4>(c), nibble);
+    let row = lookup(&ROW_BIT, hi);
+    let zero = _mm256_setzero_si256();
+    let invalid = _mm256_cmpeq_epi8(_mm256_and_si256(lookup(&VALID_IN_ROWS, lo), row), zero);
+    let not_skipped = _mm256_cmpeq_epi8(_mm256_and_si256(lookup(&SKIPPED_IN_ROWS, lo), row), zero);
+    /* not_skipped is -1, adding back the 1 */
+    let digits = _mm256_sub_epi8(_mm256_sub_epi8(c, lookup(&OFFSET_PLUS_1, hi)), not_skipped);
+    (digits, _mm256_movemask_epi8(invalid) as u32)
+}
+
+/// [`decode_fixed`](crate::decode_fixed::decode_fixed) with the Bitcoin alphabet.  The
+/// characters are validated and converted to digits 32 at a time in a single pass,
+/// the rest is shared with the scalar code.
+///
+/// # Safety
+///
+/// The CPU must support AVX2, see [`is_available`](crate::encode_avx2::is_available).
+#[target_feature(enable = "avx2")]
+pub(crate) unsafe fn decode_fixed_avx2<const N: usize>(
+    encoded_bytes: &[u8],
+) -> Result<[u8; N], Error> {
+    let () = Fixed::<N>::VALID;
+    check_max_len::<N>(encoded_bytes)?;
+    let raw58_sz = Fixed::<N>::RAW58_SZ;
+
+    /* Pad with '1's, which are 0 digits, both in front to make it exactly
+    RAW58_SZ characters and after it to fill the last vector */
+    let prepend_0 = raw58_sz - encoded_bytes.len();
+    let mut raw_base58: Scratch<[u8; RAW_BUF_SZ]> = Scratch::new([b'1'; RAW_BUF_SZ]);
+    raw_base58[prepend_0..raw58_sz].copy_from_slice(encoded_bytes);
+
+    for (k, chunk) in raw_base58
+        .chunks_exact_mut(32)
+        .take(raw58_sz.div_ceil(32))
+        .enumerate()
+    {
+        let (digits, invalid) = to_digits(_mm256_loadu_si256(chunk.as_ptr().cast()));
+        if invalid != 0 {
+            let index = 32 * k + invalid.trailing_zeros() as usize - prepend_0;
+            return Err(Error::invalid_character(encoded_bytes, index));
+        }
+        _mm256_storeu_si256(chunk.as_mut_ptr().cast(), digits);
+    }
+    check_min_len::<N>(encoded_bytes)?;
+
+    decode_raw_base58(encoded_bytes, &raw_base58[..raw58_sz], &Alphabet::BITCOIN)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        encode_avx2::is_available,
+        fixed::{check_decoder, for_each_test_width, test_encoded},
+    };
+
+    use super::decode_fixed_avx2;
+
+    fn check<const N: usize>(encoded: &[u8]) {
+        // SAFETY: the callers checked is_available
+        check_decoder::<N>(encoded, |encoded| unsafe { decode_fixed_avx2(encoded) });
+    }
+
+    #[test]
+    #[cfg_attr(miri, ignore)]
+    fn test_decode_avx2() {
+        if !is_available() {
+            return;
+        }
+        for encoded in test_encoded() {
+            for_each_test_width!(check, &encoded);
+        }
+    }
+}
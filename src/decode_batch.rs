@@ -0,0 +1,100 @@
+use alloc::vec::Vec;
+
+use crate::{alphabet::Alphabet, decode_fixed::decode_fixed_fast, fixed::Fixed, Error};
+
+/// Decodes every input into the matching output, returning one result per input.  Failed
+/// outputs are left alone.  This is only a convenience: each input is decoded on its own,
+/// like [`decode_32`](crate::decode_32), with no work shared between them.
+pub(crate) fn decode_fixed_batch<const N: usize, I: AsRef<[u8]>>(
+    inputs: &[I],
+    outputs: &mut [[u8; N]],
+) -> Vec<Result<(), Error>> {
+    let () = Fixed::<N>::VALID;
+    assert!(outputs.len() >= inputs.len());
+
+    let mut results = Vec::with_capacity(inputs.len());
+    for (input, output) in inputs.iter().zip(outputs) {
+        let decoded = decode_fixed_fast(input.as_ref(), &Alphabet::BITCOIN);
+        results.push(decoded.map(|decoded| *output = decoded));
+    }
+    results
+}
+
+#[cfg(test)]
+mod tests {
+    use proptest::prelude::*;
+
+    use crate::{alphabet::Alphabet, decode_fixed::decode_fixed, encode_fixed::encode_fixed};
+
+    use super::decode_fixed_batch;
+
+    fn check<const N: usize>(inputs: &[Vec<u8>]) {
+        let mut outputs = vec![[0xAA; N]; inputs.len()];
+        let results = decode_fixed_batch(inputs, &mut outputs);
+        assert_eq!(results.len(), inputs.len());
+        for ((input, output), result) in inputs.iter().zip(&outputs).zip(results) {
+            let expected = decode_fixed::<N>(input, &Alphabet::BITCOIN);
+            /* Failed outputs are left alone */
+            assert_eq!(*output, *expected.as_ref().unwrap_or(&[0xAA; N]));
+            assert_eq!(result, expected.map(|_| ()));
+        }
+    }
+
+    /// Valid encodings, valid encodings with one change, and plain garbage
+    fn input<const N: usize>() -> impl Strategy<Value = Vec<u8>> {
+        let valid = (prop::array::uniform32(any::<u8>()), 0..4usize).prop_map(|(seed, zeros)| {
+            let mut bytes: [u8; N] = core::array::from_fn(|i| seed[i % 32]);
+            bytes[..zeros].fill(0);
+            encode_fixed(&bytes, &Alphabet::BITCOIN).into_bytes()
+        });
+        let changed = (valid.clone(), any::<prop::sample::Index>(), any::<u8>()).prop_map(
+            |(mut encoded, index, c)| {
+                let index = index.index(encoded.len());
+                match c % 4 {
+                    0 => encoded[index] = c,
+                    1 => encoded.insert(index, c),
+                    2 => encoded.insert(0, b'1'),
+                    _ => drop(encoded.remove(0)),
+                }
+                encoded
+            },
+        );
+        prop_oneof![
+            4 => valid,
+            2 => changed,
+            1 => prop::collection::vec(b'1'..=b'z', 0..=N * 2),
+        ]
+    }
+
+    proptest! {
+        #[test]
+        #[cfg_attr(miri, ignore)]
+        fn test_batch_matches_decode_32(inputs in prop::collection::vec(input::<32>(), 0..40)) {
+            check::<32>(&inputs);
+        }
+
+        #[test]
+        #[cfg_attr(miri, ignore)]
+        fn test_batch_matches_decode_64(inputs in prop::collection::vec(input::<64>(), 0..40)) {
+            check::<64>(&inputs);
+        }
+
+        #[test]
+        #[cfg_attr(miri, ignore)]
+        fn test_batch_matches_decode_33(inputs in prop::collection::vec(input::<33>(), 0..20)) {
+            check::<33>(&inputs);
+        }
+
+        #[test]
+        #[cfg_attr(miri, ignore)]
+        fn test_batch_matches_decode_128(inputs in prop::collection::vec(input::<128>(), 0..20)) {
+            check::<128>(&inputs);
+        }
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_short_outputs() {
+        decode_fixed_batch(&["1"; 2], &mut [[0; 32]; 1]);
+    }
+}
@@ -0,0 +1,504 @@
+use crate::{
+    alphabet::Alphabet,
+    constants::{BASE58_INVALID_CHAR, BYTE_COUNT_32, BYTE_COUNT_64},
+    decode_32::decode_32,
+    decode_64::decode_64,
+    fixed::{Fixed, MAX_BINARY_SZ, MAX_INTERMEDIATE_SZ, MAX_RAW58_SZ},
+    scratch::{init_prefix, Scratch},
+    Error,
+};
+use core::mem::MaybeUninit;
+
+/// Fails if `encoded_bytes` is longer than any encoding of `N` bytes.  This comes first, the
+/// buffers only have room for the longest encoding.
+pub(crate) fn check_max_len<const N: usize>(encoded_bytes: &[u8]) -> Result<(), Error> {
+    if encoded_bytes.len() > Fixed::<N>::ENCODED_LEN {
+        return Err(Error::InputTooLong {
+            max: Fixed::<N>::ENCODED_LEN,
+            actual: encoded_bytes.len(),
+        });
+    }
+    Ok(())
+}
+
+/// Fails if `encoded_bytes` is shorter than any encoding of `N` bytes.  The shortest ones are
+/// `N` characters long, all leading '1's but the last.  This comes after the characters are
+/// validated, so something that isn't base58 at all is reported as such.
+pub(crate) fn check_min_len<const N: usize>(encoded_bytes: &[u8]) -> Result<(), Error> {
+    if encoded_bytes.len() < N {
+        return Err(Error::InputTooShort {
+            min: N,
+            actual: encoded_bytes.len(),
+        });
+    }
+    Ok(())
+}
+
+pub(crate) fn decode_fixed<const N: usize>(
+    encoded_bytes: &[u8],
+    alphabet: &Alphabet,
+) -> Result<[u8; N], Error> {
+    let () = Fixed::<N>::VALID;
+    check_max_len::<N>(encoded_bytes)?;
+
+    /* X = sum_i raw_base58[i] * 58^(RAW58_SZ-1-i) */
+    let mut raw_base58_buf = Scratch::new([MaybeUninit::uninit(); MAX_RAW58_SZ]);
+    let raw_base58 = init_prefix(&mut raw_base58_buf[..], Fixed::<N>::RAW58_SZ, 0u8);
+
+    /* Validate the string and convert it to digits in a single pass,
+    prepending enough 0s to make it exactly RAW58_SZ characters */
+    let prepend_0 = Fixed::<N>::RAW58_SZ - encoded_bytes.len();
+    for (index, c) in encoded_bytes.iter().enumerate() {
+        let digit = alphabet.decode[*c as usize];
+        if digit == BASE58_INVALID_CHAR {
+            return Err(Error::invalid_character(encoded_bytes, index));
+        }
+        raw_base58[prepend_0 + index] = digit;
+    }
+    check_min_len::<N>(encoded_bytes)?;
+
+    decode_raw_base58(encoded_bytes, raw_base58, alphabet)
+}
+
+/// [`decode_fixed`], going through the optimized [`decode_32`] and [`decode_64`] when `N`
+/// is 32 or 64 and `alphabet` is the Bitcoin one, which is the only one the SIMD versions
+/// handle.
+pub(crate) fn decode_fixed_fast<const N: usize>(
+    encoded_bytes: &[u8],
+    alphabet: &Alphabet,
+) -> Result<[u8; N], Error> {
+    if alphabet.encode == Alphabet::BITCOIN.encode {
+        if N == BYTE_COUNT_32 {
+            return decode_32(encoded_bytes).map(|bytes| bytes[..].try_into().unwrap());
+        }
+        if N == BYTE_COUNT_64 {
+            return decode_64(encoded_bytes).map(|bytes| bytes[..].try_into().unwrap());
+        }
+    }
+    decode_fixed(encoded_bytes, alphabet)
+}
+
+/// [`decode_fixed`] for input that is already known to only contain characters of the
+/// Bitcoin alphabet, such as a [`Base58Str`](crate::Base58Str).  The SIMD versions are used
+/// when the CPU supports them, like in [`decode_32`], since they validate the characters in
+/// the same pass that converts them.  The scalar code skips checking them again.  The length
+/// and the value are still checked.
+pub(crate) fn decode_fixed_validated<const N: usize>(
+    encoded_bytes: &[u8],
+) -> Result<[u8; N], Error> {
+    let () = Fixed::<N>::VALID;
+    #[cfg(all(target_arch = "x86_64", not(feature = "force-scalar")))]
+    if crate::encode_avx2::is_available() {
+        // SAFETY: AVX2 support was just checked
+        return unsafe { crate::decode_avx2::decode_fixed_avx2(encoded_bytes) };
+    }
+    #[cfg(all(target_arch = "x86_64", not(feature = "force-scalar")))]
+    if crate::decode_sse41::is_available() {
+        // SAFETY: SSE4.1 support was just checked
+        return unsafe { crate::decode_sse41::decode_fixed_sse41(encoded_bytes) };
+    }
+    #[cfg(all(target_arch = "aarch64", not(feature = "force-scalar")))]
+    if crate::encode_neon::is_available() {
+        // SAFETY: NEON support was just checked
+        return unsafe { crate::decode_neon::decode_fixed_neon(encoded_bytes) };
+    }
+
+    check_max_len::<N>(encoded_bytes)?;
+    check_min_len::<N>(encoded_bytes)?;
+
+    let mut raw_base58_buf = Scratch::new([MaybeUninit::uninit(); MAX_RAW58_SZ]);
+    let raw_base58 = init_prefix(&mut raw_base58_buf[..], Fixed::<N>::RAW58_SZ, 0u8);
+    let prepend_0 = Fixed::<N>::RAW58_SZ - encoded_bytes.len();
+    for (digit, c) in raw_base58[prepend_0..].iter_mut().zip(encoded_bytes) {
+        *digit = Alphabet::BITCOIN.decode[*c as usize];
+    }
+    debug_assert!(!raw_base58.contains(&BASE58_INVALID_CHAR));
+
+    decode_raw_base58(encoded_bytes, raw_base58, &Alphabet::BITCOIN)
+}
+
+/// The rest of [`decode_fixed`] once `encoded_bytes` has been validated and converted to
+/// the first `RAW58_SZ` digits of `raw_base58`, which is shared with the SIMD versions of
+/// the first part.
+#[allow(clippy::needless_range_loop)]
+pub(crate) fn decode_raw_base58<const N: usize>(
+    encoded_bytes: &[u8],
+    raw_base58: &[u8],
+    alphabet: &Alphabet,
+) -> Result<[u8; N], Error> {
+    let binary_sz = Fixed::<N>::BINARY_SZ;
+    let intermediate_sz = Fixed::<N>::INTERMEDIATE_SZ;
+
+    /* Convert to the intermediate format (base 58^5):
+    X = sum_i intermediate[i] * 58^(5*(INTERMEDIATE_SZ-1-i)) */
+
+    let mut intermediate_buf = Scratch::new([MaybeUninit::uninit(); MAX_INTERMEDIATE_SZ]);
+    let intermediate = init_prefix(&mut intermediate_buf[..], intermediate_sz, 0u64);
+    for i in 0..intermediate_sz {
+        intermediate[i] = (raw_base58[5 * i] as u64) * 11_316_496
+            + (raw_base58[5 * i + 1] as u64) * 195_112
+            + (raw_base58[5 * i + 2] as u64) * 3_364
+            + (raw_base58[5 * i + 3] as u64) * 58
+            + (raw_base58[5 * i + 4] as u64);
+    }
+
+    /* Using the table, convert to overcomplete base 2^32 (terms can be
+    larger than 2^32).  We need to be careful about overflow.
+    For N==32, the largest anything in binary can get is binary[7]:
+    even if intermediate[i]==58^5-1 for all i, then binary[7] < 2^63.
+    For N==64, the largest anything in binary can get is binary[13]:
+    even if intermediate[i]==58^5-1 for all i, then binary[13] <
+    2^63.998.  Hanging in there, just by a thread!
+    Wider N wouldn't make it, so DEC_REDUCE, worked out at compile
+    time, says which terms to carry into the next one before they
+    could overflow.  It's empty for N==32 and N==64. */
+
+    let mut binary_buf = Scratch::new([MaybeUninit::uninit(); MAX_BINARY_SZ]);
+    let binary = init_prefix(&mut binary_buf[..], binary_sz, 0u64);
+    if Fixed::<N>::DEC_REDUCE_ANY {
+        let mut start = 0;
+        while start < intermediate_sz {
+            let end = Fixed::<N>::DEC_SEGMENT_END[start];
+            for i in start..end {
+                for j in 0..binary_sz {
+                    binary[j] += intermediate[i] * Fixed::<N>::DEC_TABLE[i][j];
+                }
+            }
+            if end < intermediate_sz {
+                let reduce = Fixed::<N>::DEC_REDUCE[end];
+                for j in (1..binary_sz).rev() {
+                    if reduce & (1 << j) != 0 {
+                        binary[j - 1] += binary[j] >> 32;
+                        binary[j] &= 0xFFFFFFFF;
+                    }
+                }
+            }
+            start = end;
+        }
+    } else {
+        for j in 0..binary_sz {
+            let mut acc: u64 = 0;
+            for i in 0..intermediate_sz {
+                acc += intermediate[i] * Fixed::<N>::DEC_TABLE[i][j];
+            }
+            binary[j] = acc;
+        }
+    }
+
+    /* Make sure each term is less than 2^32.
+    For N==32, we have plenty of headroom in binary, so overflow is
+    not a concern this time.
+    For N==64, even if we add 2^32 to binary[13], it is still 2^63.998,
+    so this won't overflow. */
+
+    for i in (1..binary_sz).rev() {
+        binary[i - 1] += binary[i] >> 32;
+        binary[i] &= 0xFFFFFFFF;
+    }
+
+    decode_binary(encoded_bytes, binary, alphabet)
+}
+
+/// The end of [`decode_fixed`] once each of the first `BINARY_SZ` terms of `binary` is less
+/// than 2^32, which is also shared with the SIMD versions.
+pub(crate) fn decode_binary<const N: usize>(
+    encoded_bytes: &[u8],
+    binary: &[u64],
+    alphabet: &Alphabet,
+) -> Result<[u8; N], Error> {
+    let binary_sz = Fixed::<N>::BINARY_SZ;
+
+    /* If the largest term is 2^32 or bigger, it means N is larger than
+    what can fit in BYTE_CNT bytes.  This can be triggered, by passing
+    a base58 string of all 'z's for example.  When N isn't a multiple
+    of 4, the largest term only has room for HEAD_BYTES bytes. */
+
+    if binary[0] > Fixed::<N>::HEAD_LIMB_MAX {
+        return Err(Error::InvalidByteAmount);
+    }
+
+    let mut out: [u8; N] = [0; N];
+    let head = N % 4;
+    if head != 0 {
+        out[..head].copy_from_slice(&(binary[0] as u32).to_be_bytes()[4 - head..]);
+    }
+    let full_limbs = &binary[usize::from(head != 0)..binary_sz];
+    for (chunk, limb) in out[head..].chunks_exact_mut(4).zip(full_limbs) {
+        chunk.copy_from_slice(&(*limb as u32).to_be_bytes());
+    }
+
+    /* Make sure the encoded version has the same number of leading '1's
+    (or whatever the alphabet uses for 0) as the decoded version has
+    leading 0s.  Otherwise it encodes a different number of bytes. */
+
+    let zero = alphabet.encode[0];
+    let mut leading_zero_cnt: usize = 0;
+    while leading_zero_cnt < N {
+        if out[leading_zero_cnt] != 0 {
+            break;
+        }
+        if leading_zero_cnt >= encoded_bytes.len() || encoded_bytes[leading_zero_cnt] != zero {
+            return Err(Error::InvalidByteAmount);
+        }
+        leading_zero_cnt += 1;
+    }
+
+    if leading_zero_cnt < encoded_bytes.len() && encoded_bytes[leading_zero_cnt] == zero {
+        return Err(Error::InvalidByteAmount);
+    }
+
+    Ok(out)
+}
+
+/* The const fn pieces of decode_fixed, shared by decode_32_const and
+decode_64_const.  Iterators, slice copies and ? aren't available in
+const fn, hence the while loops and matches.  Only the table multiply
+differs between the widths, so that part lives with each of them. */
+
+/// The first steps of [`decode_fixed`] as a `const fn` for the Bitcoin alphabet: checks the
+/// length and converts `encoded_bytes` to the intermediate format, base 58^5.
+pub(crate) const fn intermediate_const<const N: usize, const INTERMEDIATE_SZ: usize>(
+    encoded_bytes: &[u8],
+) -> Result<[u64; INTERMEDIATE_SZ], Error> {
+    let raw58_sz = 5 * INTERMEDIATE_SZ;
+    if encoded_bytes.len() > Fixed::<N>::ENCODED_LEN {
+        return Err(Error::InputTooLong {
+            max: Fixed::<N>::ENCODED_LEN,
+            actual: encoded_bytes.len(),
+        });
+    }
+
+    let mut raw_base58 = [0; MAX_RAW58_SZ];
+    let prepend_0 = raw58_sz - encoded_bytes.len();
+    let mut index = 0;
+    while index < encoded_bytes.len() {
+        let digit = Alphabet::BITCOIN.decode[encoded_bytes[index] as usize];
+        if digit == BASE58_INVALID_CHAR {
+            return Err(Error::InvalidCharacter {
+                character: char_at_const(encoded_bytes, index),
+                index,
+            });
+        }
+        raw_base58[prepend_0 + index] = digit;
+        index += 1;
+    }
+    if encoded_bytes.len() < N {
+        return Err(Error::InputTooShort {
+            min: N,
+            actual: encoded_bytes.len(),
+        });
+    }
+
+    let mut intermediate = [0; INTERMEDIATE_SZ];
+    let mut i = 0;
+    while i < INTERMEDIATE_SZ {
+        intermediate[i] = (raw_base58[5 * i] as u64) * 11_316_496
+            + (raw_base58[5 * i + 1] as u64) * 195_112
+            + (raw_base58[5 * i + 2] as u64) * 3_364
+            + (raw_base58[5 * i + 3] as u64) * 58
+            + (raw_base58[5 * i + 4] as u64);
+        i += 1;
+    }
+    Ok(intermediate)
+}
+
+/// The character starting at byte `index` of `bytes`, like [`Error::invalid_character`]
+/// finds it.  The input of the const decoders is a `&str`, so it is valid UTF-8.
+const fn char_at_const(bytes: &[u8], index: usize) -> char {
+    let lead = bytes[index] as u32;
+    let (len, mut c) = match lead {
+        0x00..=0x7F => (1, lead),
+        0xC0..=0xDF => (2, lead & 0x1F),
+        0xE0..=0xEF => (3, lead & 0x0F),
+        _ => (4, lead & 0x07),
+    };
+    let mut i = 1;
+    while i < len && index + i < bytes.len() {
+        c = (c << 6) | (bytes[index + i] as u32 & 0x3F);
+        i += 1;
+    }
+    match char::from_u32(c) {
+        Some(c) => c,
+        None => char::REPLACEMENT_CHARACTER,
+    }
+}
+
+/// [`decode_binary`] as a `const fn` for the Bitcoin alphabet, for widths that are a
+/// multiple of 4 bytes
+pub(crate) const fn decode_binary_const<const N: usize, const BINARY_SZ: usize>(
+    encoded_bytes: &[u8],
+    binary: &[u64; BINARY_SZ],
+) -> Result<[u8; N], Error> {
+    if binary[0] > u32::MAX as u64 {
+        return Err(Error::InvalidByteAmount);
+    }
+
+    let mut out = [0; N];
+    let mut i = 0;
+    while i < N {
+        out[i] = (binary[i / 4] >> (8 * (3 - i % 4))) as u8;
+        i += 1;
+    }
+
+    let zero = Alphabet::BITCOIN.encode[0];
+    let mut out_leading_0s = 0;
+    while out_leading_0s < N && out[out_leading_0s] == 0 {
+        out_leading_0s += 1;
+    }
+    let mut in_leading_1s = 0;
+    while in_leading_1s < encoded_bytes.len() && encoded_bytes[in_leading_1s] == zero {
+        in_leading_1s += 1;
+    }
+    if in_leading_1s != out_leading_0s {
+        return Err(Error::InvalidByteAmount);
+    }
+    Ok(out)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::alphabet::Alphabet;
+    use crate::encode_fixed::{encode_fixed_into, encode_fixed_into_fast};
+    use crate::fixed::{for_each_test_width, test_inputs};
+    use crate::Error;
+
+    use super::{decode_fixed, decode_fixed_fast};
+
+    fn check<const N: usize>() {
+        let inputs = test_inputs::<N>(16, 4);
+        for bytes in inputs {
+            let mut buf = [0; 256];
+            let len = encode_fixed_into(&bytes, &mut buf, &Alphabet::BITCOIN);
+            assert_eq!(
+                decode_fixed::<N>(&buf[..len], &Alphabet::BITCOIN),
+                Ok(bytes)
+            );
+            assert_eq!(bs58::decode(&buf[..len]).into_vec().unwrap(), bytes);
+        }
+
+        // one byte too many
+        let encoded =
+            bs58::encode([255; N].iter().chain(&[255]).copied().collect::<Vec<_>>()).into_string();
+        assert!(decode_fixed::<N>(encoded.as_bytes(), &Alphabet::BITCOIN).is_err());
+        // the smallest N+1 byte value
+        assert!(decode_fixed::<N>("1".repeat(N + 1).as_bytes(), &Alphabet::BITCOIN).is_err());
+        // one byte too few
+        assert!(decode_fixed::<N>("1".repeat(N - 1).as_bytes(), &Alphabet::BITCOIN).is_err());
+    }
+
+    #[test]
+    fn test_decode_fixed() {
+        for_each_test_width!(check);
+    }
+
+    #[test]
+    fn test_partial_head_limb() {
+        // 0x01_00 overflows a single byte even though it fits the limb
+        assert_eq!(
+            decode_fixed::<1>(b"5R", &Alphabet::BITCOIN),
+            Err(Error::InvalidByteAmount)
+        );
+        assert_eq!(decode_fixed::<1>(b"5Q", &Alphabet::BITCOIN), Ok([255]));
+        assert_eq!(
+            decode_fixed::<33>("l".repeat(33).as_bytes(), &Alphabet::BITCOIN),
+            Err(Error::InvalidCharacter {
+                character: 'l',
+                index: 0
+            })
+        );
+    }
+
+    /// The dispatching versions give the same results as the generic ones, whichever
+    /// path they take
+    fn check_fast<const N: usize>() {
+        for alphabet in [Alphabet::BITCOIN, Alphabet::RIPPLE] {
+            for bytes in test_inputs::<N>(4, 3) {
+                let (mut expected, mut out) = ([0; 128], [0; 128]);
+                let len = encode_fixed_into(&bytes, &mut expected, &alphabet);
+                assert_eq!(encode_fixed_into_fast(&bytes, &mut out, &alphabet), len);
+                assert_eq!(out[..len], expected[..len]);
+                for encoded in [&expected[..len], &expected[1..len], b"0"] {
+                    assert_eq!(
+                        decode_fixed_fast::<N>(encoded, &alphabet),
+                        decode_fixed::<N>(encoded, &alphabet)
+                    );
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_fast_paths() {
+        check_fast::<32>();
+        check_fast::<33>();
+        check_fast::<64>();
+    }
+
+    #[test]
+    fn test_alphabets() {
+        let mut bytes: [u8; 25] = core::array::from_fn(|i| (i as u8).wrapping_mul(167));
+        bytes[..2].fill(0);
+        for (alphabet, bs58_alphabet) in [
+            (Alphabet::RIPPLE, bs58::Alphabet::RIPPLE),
+            (Alphabet::FLICKR, bs58::Alphabet::FLICKR),
+        ] {
+            let encoded = bs58::encode(bytes)
+                .with_alphabet(bs58_alphabet)
+                .into_string();
+            assert_eq!(decode_fixed::<25>(encoded.as_bytes(), &alphabet), Ok(bytes));
+
+            // one leading zero too many, spelled with this alphabet's zero digit
+            let encoded = alphabet.as_str()[..1].to_string() + &encoded;
+            assert!(decode_fixed::<25>(encoded.as_bytes(), &alphabet).is_err());
+        }
+    }
+
+    #[test]
+    fn test_error_details() {
+        let bitcoin = &Alphabet::BITCOIN;
+        assert_eq!(
+            decode_fixed::<4>("1z€z".as_bytes(), bitcoin),
+            Err(Error::InvalidCharacter {
+                character: '€',
+                index: 2
+            })
+        );
+        assert_eq!(
+            decode_fixed::<4>(&[b'z', b'z', b'z', 0xff], bitcoin),
+            Err(Error::InvalidCharacter {
+                character: char::REPLACEMENT_CHARACTER,
+                index: 3
+            })
+        );
+        assert_eq!(
+            decode_fixed::<4>(b"1111111", bitcoin),
+            Err(Error::InputTooLong { max: 6, actual: 7 })
+        );
+        // 4 bytes take 4 to 6 characters
+        assert_eq!(
+            decode_fixed::<4>(b"112", bitcoin),
+            Err(Error::InputTooShort { min: 4, actual: 3 })
+        );
+        // [0, 0, 0, 1] is "1112", so one leading '1' is missing or one is extra
+        assert_eq!(
+            decode_fixed::<4>(b"1122", bitcoin),
+            Err(Error::InvalidByteAmount)
+        );
+        assert_eq!(
+            decode_fixed::<4>(b"11112", bitcoin),
+            Err(Error::InvalidByteAmount)
+        );
+        assert_eq!(
+            decode_fixed::<4>(b"", bitcoin),
+            Err(Error::InputTooShort { min: 4, actual: 0 })
+        );
+        // characters are checked before the minimum length
+        assert_eq!(
+            decode_fixed::<4>(b"l", bitcoin),
+            Err(Error::InvalidCharacter {
+                character: 'l',
+                index: 0
+            })
+        );
+    }
+}
@@ -0,0 +1,196 @@
+use core::arch::aarch64::*;
+
+use crate::{
+    alphabet::Alphabet,
+    constants::BASE58_INVALID_CHAR,
+    decode_fixed::{check_max_len, check_min_len, decode_binary},
+    encode_neon::nibble_mask,
+    fixed::{Fixed, MAX_BINARY_SZ, MAX_INTERMEDIATE_SZ, MAX_RAW58_SZ},
+    scratch::Scratch,
+    Error,
+};
+
+/* Whole vectors covering the longest raw_base58 */
+const RAW_BUF_SZ: usize = MAX_RAW58_SZ.div_ceil(16) * 16;
+
+/* Binary terms are kept 2 to a vector as u64s */
+const MAX_VECS: usize = MAX_BINARY_SZ.div_ceil(2);
+
+/* The ASCII half of the Bitcoin decode table, for two 4 register lookups.
+Everything from 0x80 up is invalid. */
+const BITCOIN_DIGITS: [u8; 128] = ascii_half(&Alphabet::BITCOIN.decode);
+
+struct Neon<const N: usize>;
+
+impl<const N: usize> Neon<N> {
+    const VECS: usize = Fixed::<N>::BINARY_SZ.div_ceil(2);
+    /// DEC_TABLE as u32s, with every row padded with zeros to a whole number of vectors
+    const DEC_TABLE: [[u32; 2 * MAX_VECS]; MAX_INTERMEDIATE_SZ] =
+        narrow_rows(&Fixed::<N>::DEC_TABLE);
+}
+
+const fn narrow_rows(
+    table: &[[u64; MAX_BINARY_SZ]; MAX_INTERMEDIATE_SZ],
+) -> [[u32; 2 * MAX_VECS]; MAX_INTERMEDIATE_SZ] {
+    let mut out = [[0; 2 * MAX_VECS]; MAX_INTERMEDIATE_SZ];
+    let mut i = 0;
+    while i < MAX_INTERMEDIATE_SZ {
+        let mut j = 0;
+        while j < MAX_BINARY_SZ {
+            /* Every entry is below 2^32 */
+            out[i][j] = table[i][j] as u32;
+            j += 1;
+        }
+        i += 1;
+    }
+    out
+}
+
+const fn ascii_half(decode: &[u8; 256]) -> [u8; 128] {
+    let mut out = [0; 128];
+    let mut i = 0;
+    while i < 128 {
+        out[i] = decode[i];
+        i += 1;
+    }
+    out
+}
+
+/// Maps every byte to its digit in the Bitcoin alphabet.  Returns the digits and
+/// a mask, 0xFF or 0 in every byte, of the bytes that aren't in the alphabet.
+/// `vqtbl4q_u8` gives 0 for indices from 64 up and `vqtbx4q_u8` leaves them
+/// alone, so each byte is only looked up in the half of the table it falls in.
+#[target_feature(enable = "neon")]
+#[inline]
+unsafe fn to_digits(c: uint8x16_t) -> (uint8x16_t, uint8x16_t) {
+    let lo = vld1q_u8_x4(BITCOIN_DIGITS.as_ptr());
+    let hi = vld1q_u8_x4(BITCOIN_DIGITS[64..].as_ptr());
+    let d = vqtbl4q_u8(lo, c);
+    let d = vqtbx4q_u8(d, hi, vsubq_u8(c, vdupq_n_u8(64)));
+    let invalid = vorrq_u8(
+        vceqq_u8(d, vdupq_n_u8(BASE58_INVALID_CHAR)),
+        vcgeq_u8(c, vdupq_n_u8(0x80)),
+    );
+    (d, invalid)
+}
+
+/// [`decode_fixed`](crate::decode_fixed::decode_fixed) with the Bitcoin alphabet.
+/// The characters are validated and converted to digits 16 at a time, and the
+/// table multiply into binary works on whole vectors.  The carries and the
+/// checks on the result are shared with the scalar code.
+///
+/// # Safety
+///
+/// The CPU must support NEON, see [`is_available`](crate::encode_neon::is_available).
+#[target_feature(enable = "neon")]
+pub(crate) unsafe fn decode_fixed_neon<const N: usize>(
+    encoded_bytes: &[u8],
+) -> Result<[u8; N], Error> {
+    let () = Fixed::<N>::VALID;
+    check_max_len::<N>(encoded_bytes)?;
+    let binary_sz = Fixed::<N>::BINARY_SZ;
+    let intermediate_sz = Fixed::<N>::INTERMEDIATE_SZ;
+    let raw58_sz = Fixed::<N>::RAW58_SZ;
+    let vecs = Neon::<N>::VECS;
+
+    /* Pad with '1's, which are 0 digits, both in front to make it exactly
+    RAW58_SZ characters and after it to fill the last vector */
+    let prepend_0 = raw58_sz - encoded_bytes.len();
+    let mut raw_base58: Scratch<[u8; RAW_BUF_SZ]> = Scratch::new([b'1'; RAW_BUF_SZ]);
+    raw_base58[prepend_0..raw58_sz].copy_from_slice(encoded_bytes);
+
+    for (k, chunk) in raw_base58
+        .chunks_exact_mut(16)
+        .take(raw58_sz.div_ceil(16))
+        .enumerate()
+    {
+        let (digits, invalid) = to_digits(vld1q_u8(chunk.as_ptr()));
+        let invalid = nibble_mask(invalid);
+        if invalid != 0 {
+            let index = 16 * k + invalid.trailing_zeros() as usize / 4 - prepend_0;
+            return Err(Error::invalid_character(encoded_bytes, index));
+        }
+        vst1q_u8(chunk.as_mut_ptr(), digits);
+    }
+    check_min_len::<N>(encoded_bytes)?;
+
+    let mut intermediate: Scratch<[u32; MAX_INTERMEDIATE_SZ]> =
+        Scratch::new([0; MAX_INTERMEDIATE_SZ]);
+    for (term, digits) in intermediate
+        .iter_mut()
+        .zip(raw_base58.chunks_exact(5))
+        .take(intermediate_sz)
+    {
+        *term = digits.iter().fold(0, |acc, &d| acc * 58 + u32::from(d));
+    }
+
+    /* Every term is below 58^5 and every table entry below 2^32, so
+    vmlal_u32 gives the same products as the scalar code and the
+    overflow bounds and reduction schedule are the same too. */
+    let table = &Neon::<N>::DEC_TABLE;
+    let mut acc: Scratch<[uint64x2_t; MAX_VECS]> = Scratch::new([vdupq_n_u64(0); MAX_VECS]);
+    let mut binary: Scratch<[u64; 2 * MAX_VECS]> = Scratch::new([0; 2 * MAX_VECS]);
+    let mut start = 0;
+    while start < intermediate_sz {
+        let end = Fixed::<N>::DEC_SEGMENT_END[start];
+        for i in start..end {
+            let term = vdup_n_u32(intermediate[i]);
+            for v in 0..vecs {
+                let row = vld1_u32(table[i][2 * v..].as_ptr());
+                acc[v] = vmlal_u32(acc[v], term, row);
+            }
+        }
+        if end < intermediate_sz {
+            let reduce = Fixed::<N>::DEC_REDUCE[end];
+            for v in 0..vecs {
+                vst1q_u64(binary[2 * v..].as_mut_ptr(), acc[v]);
+            }
+            for j in (1..binary_sz).rev() {
+                if reduce & (1 << j) != 0 {
+                    binary[j - 1] += binary[j] >> 32;
+                    binary[j] &= 0xFFFFFFFF;
+                }
+            }
+            for v in 0..vecs {
+                acc[v] = vld1q_u64(binary[2 * v..].as_ptr());
+            }
+        }
+        start = end;
+    }
+    for v in 0..vecs {
+        vst1q_u64(binary[2 * v..].as_mut_ptr(), acc[v]);
+    }
+
+    for i in (1..binary_sz).rev() {
+        binary[i - 1] += binary[i] >> 32;
+        binary[i] &= 0xFFFFFFFF;
+    }
+
+    decode_binary(encoded_bytes, &binary[..binary_sz], &Alphabet::BITCOIN)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        encode_neon::is_available,
+        fixed::{check_decoder, for_each_test_width, test_encoded},
+    };
+
+    use super::decode_fixed_neon;
+
+    fn check<const N: usize>(encoded: &[u8]) {
+        // SAFETY: the callers checked is_available
+        check_decoder::<N>(encoded, |encoded| unsafe { decode_fixed_neon(encoded) });
+    }
+
+    #[test]
+    #[cfg_attr(miri, ignore)]
+    fn test_decode_neon() {
+        if !is_available() {
+            return;
+        }
+        for encoded in test_encoded() {
+            for_each_test_width!(check, &encoded);
+        }
+    }
+}
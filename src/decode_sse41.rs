@@ -0,0 +1,109 @@
+use core::arch::x86_64::*;
+
+use crate::{
+    alphabet::Alphabet,
+    decode_avx2::{OFFSET_PLUS_1, ROW_BIT, SKIPPED_IN_ROWS, VALID_IN_ROWS},
+    decode_fixed::{check_max_len, check_min_len, decode_raw_base58},
+    fixed::{Fixed, MAX_RAW58_SZ},
+    scratch::Scratch,
+    Error,
+};
+
+/* Whole vectors covering the longest raw_base58 */
+const RAW_BUF_SZ: usize = MAX_RAW58_SZ.div_ceil(16) * 16;
+
+/// Whether [`decode_fixed_sse41`] can run on this CPU.  Without `std` there is no
+/// runtime detection, so it has to be enabled at compile time.
+#[inline]
+pub(crate) fn is_available() -> bool {
+    #[cfg(feature = "std")]
+    {
+        std::is_x86_feature_detected!("sse4.1")
+    }
+    #[cfg(not(feature = "std"))]
+    {
+        cfg!(target_feature = "sse4.1")
+    }
+}
+
+/// `table[nibble]` in every byte
+#[target_feature(enable = "sse4.1")]
+#[inline]
+unsafe fn lookup(table: &[u8; 16], nibbles: __m128i) -> __m128i {
+    _mm_shuffle_epi8(_mm_loadu_si128(table.as_ptr().cast()), nibbles)
+}
+
+/// The AVX2 `to_digits` on half as many bytes, see there for the tables.
+#[target_feature(enable = "sse4.1")]
+#[inline]
+unsafe fn to_digits(c: __m128i) -> (__m128i, u32) {
+    let nibble = _mm_set1_epi8(0x0F);
+    let lo = _mm_and_si128(c, nibble);
+    let hi = _mm_and_si128(_mm_srli_epi16::<4>(c), nibble);
+    let row = lookup(&ROW_BIT, hi);
+    let zero = _mm_setzero_si128();
+    let invalid = _mm_cmpeq_epi8(_mm_and_si128(lookup(&VALID_IN_ROWS, lo), row), zero);
+    let not_skipped = _mm_cmpeq_epi8(_mm_and_si128(lookup(&SKIPPED_IN_ROWS, lo), row), zero);
+    let digits = _mm_sub_epi8(_mm_sub_epi8(c, lookup(&OFFSET_PLUS_1, hi)), not_skipped);
+    (digits, _mm_movemask_epi8(invalid) as u32)
+}
+
+/// [`decode_fixed`](crate::decode_fixed::decode_fixed) with the Bitcoin alphabet, for
+/// CPUs without AVX2.  The characters are validated and converted to digits 16 at a
+/// time in a single pass, the rest is shared with the scalar code.
+///
+/// # Safety
+///
+/// The CPU must support SSE4.1, see [`is_available`].
+#[target_feature(enable = "sse4.1")]
+pub(crate) unsafe fn decode_fixed_sse41<const N: usize>(
+    encoded_bytes: &[u8],
+) -> Result<[u8; N], Error> {
+    let () = Fixed::<N>::VALID;
+    check_max_len::<N>(encoded_bytes)?;
+    let raw58_sz = Fixed::<N>::RAW58_SZ;
+
+    /* Same padding with '1's as decode_fixed_avx2 */
+    let prepend_0 = raw58_sz - encoded_bytes.len();
+    let mut raw_base58: Scratch<[u8; RAW_BUF_SZ]> = Scratch::new([b'1'; RAW_BUF_SZ]);
+    raw_base58[prepend_0..raw58_sz].copy_from_slice(encoded_bytes);
+
+    for (k, chunk) in raw_base58
+        .chunks_exact_mut(16)
+        .take(raw58_sz.div_ceil(16))
+        .enumerate()
+    {
+        let (digits, invalid) = to_digits(_mm_loadu_si128(chunk.as_ptr().cast()));
+        if invalid != 0 {
+            let index = 16 * k + invalid.trailing_zeros() as usize - prepend_0;
+            return Err(Error::invalid_character(encoded_bytes, index));
+        }
+        _mm_storeu_si128(chunk.as_mut_ptr().cast(), digits);
+    }
+    check_min_len::<N>(encoded_bytes)?;
+
+    decode_raw_base58(encoded_bytes, &raw_base58[..raw58_sz], &Alphabet::BITCOIN)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::fixed::{check_decoder, for_each_test_width, test_encoded};
+
+    use super::{decode_fixed_sse41, is_available};
+
+    fn check<const N: usize>(encoded: &[u8]) {
+        // SAFETY: the callers checked is_available
+        check_decoder::<N>(encoded, |encoded| unsafe { decode_fixed_sse41(encoded) });
+    }
+
+    #[test]
+    #[cfg_attr(miri, ignore)]
+    fn test_decode_sse41() {
+        if !is_available() {
+            return;
+        }
+        for encoded in test_encoded() {
+            for_each_test_width!(check, &encoded);
+        }
+    }
+}
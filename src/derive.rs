@@ -0,0 +1,147 @@
+//! Support for the code generated by [`Base58`](crate::Base58). Not part of the public API.
+
+use core::fmt;
+
+use crate::{
+    alphabet::Alphabet, decode_fixed::decode_fixed_fast, encode_fixed::encode_fixed_into_fast,
+    fixed::MAX_ENCODED_LEN, scratch::Scratch, Error,
+};
+
+#[cfg(feature = "serde")]
+pub use ::serde;
+
+/// Encodes `bytes` into `buf` and returns the encoding.  Without an alphabet, 32 and 64
+/// bytes go through the optimized codecs; the derive only allows those two widths.
+fn encode<'a, const N: usize>(
+    bytes: &[u8; N],
+    alphabet: Option<&Alphabet>,
+    buf: &'a mut [u8; MAX_ENCODED_LEN],
+) -> &'a str {
+    let len = encode_fixed_into_fast(bytes, &mut buf[..], alphabet.unwrap_or(&Alphabet::BITCOIN));
+    // SAFETY: every byte written by the encoders comes from an ASCII base58 alphabet
+    unsafe { core::str::from_utf8_unchecked(&buf[..len]) }
+}
+
+pub fn fmt_display<const N: usize>(
+    bytes: &[u8; N],
+    alphabet: Option<&Alphabet>,
+    f: &mut fmt::Formatter<'_>,
+) -> fmt::Result {
+    let mut buf = Scratch::new([0; MAX_ENCODED_LEN]);
+    f.pad(encode(bytes, alphabet, &mut buf))
+}
+
+/// Writes `Name(<base58>)`, or `Name(<first 4>…<last 4>)` when `truncated`
+pub fn fmt_debug<const N: usize>(
+    name: &str,
+    bytes: &[u8; N],
+    alphabet: Option<&Alphabet>,
+    truncated: bool,
+    f: &mut fmt::Formatter<'_>,
+) -> fmt::Result {
+    let mut buf = Scratch::new([0; MAX_ENCODED_LEN]);
+    let encoded = encode(bytes, alphabet, &mut buf);
+    let mut tuple = f.debug_tuple(name);
+    if truncated && encoded.len() > 8 {
+        let (head, tail) = (&encoded[..4], &encoded[encoded.len() - 4..]);
+        tuple.field(&format_args!("{head}…{tail}"));
+    } else {
+        tuple.field(&format_args!("{encoded}"));
+    }
+    tuple.finish()
+}
+
+pub fn from_str<const N: usize>(s: &str, alphabet: Option<&Alphabet>) -> Result<[u8; N], Error> {
+    decode_fixed_fast(s.as_bytes(), alphabet.unwrap_or(&Alphabet::BITCOIN))
+}
+
+#[cfg(feature = "serde")]
+pub fn serialize<const N: usize, S: serde::Serializer>(
+    bytes: &[u8; N],
+    alphabet: Option<&Alphabet>,
+    serializer: S,
+) -> Result<S::Ok, S::Error> {
+    crate::serde::serialize_fixed(bytes, alphabet.unwrap_or(&Alphabet::BITCOIN), serializer)
+}
+
+#[cfg(feature = "serde")]
+pub fn deserialize<'de, const N: usize, D: serde::Deserializer<'de>>(
+    deserializer: D,
+    alphabet: Option<&Alphabet>,
+) -> Result<[u8; N], D::Error> {
+    crate::serde::deserialize_fixed(deserializer, alphabet.unwrap_or(&Alphabet::BITCOIN))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{Alphabet, Base58, Error};
+
+    #[derive(Clone, Copy, PartialEq, Base58)]
+    struct Pubkey([u8; 32]);
+
+    #[derive(PartialEq, Base58)]
+    #[base58(debug = "truncated")]
+    struct Signature {
+        bytes: [u8; 64],
+    }
+
+    #[derive(PartialEq, Base58)]
+    #[base58(alphabet = Alphabet::RIPPLE)]
+    struct AccountId([u8; 32]);
+
+    const KEY: &str = "XkCriyrNwS3G4rzAXtG5B1nnvb5Ka1JtCku93VqeKAr";
+
+    #[test]
+    fn test_derive() {
+        let key: Pubkey = KEY.parse().unwrap();
+        assert_eq!(crate::decode_32(KEY).unwrap(), key.0);
+        assert_eq!(KEY, key.to_string());
+        assert_eq!(format!("Pubkey({KEY})"), format!("{key:?}"));
+        assert_eq!(format!("{KEY:>50}"), format!("{key:>50}"));
+        assert_eq!(
+            Err(Error::InvalidCharacter {
+                character: 'l',
+                index: 0
+            }),
+            "l".repeat(32).parse::<Pubkey>()
+        );
+
+        let signature = Signature { bytes: [255; 64] };
+        let encoded = crate::encode_64(&[255; 64]);
+        assert_eq!(encoded, signature.to_string());
+        assert_eq!(Ok(signature), encoded.parse::<Signature>());
+        assert_eq!(
+            format!("Signature({}…{})", &encoded[..4], &encoded[84..]),
+            format!("{:?}", Signature { bytes: [255; 64] })
+        );
+
+        let account = AccountId([0; 32]);
+        assert_eq!("r".repeat(32), account.to_string());
+        assert_eq!(Ok(account), "r".repeat(32).parse::<AccountId>());
+        assert!("1".repeat(32).parse::<AccountId>().is_err());
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_derive_serde() {
+        #[derive(PartialEq, Base58)]
+        #[base58(serde)]
+        struct Pubkey([u8; 32]);
+
+        #[derive(PartialEq, Base58)]
+        #[base58(serde, alphabet = Alphabet::FLICKR)]
+        struct FlickrId([u8; 32]);
+
+        let key: Pubkey = KEY.parse().unwrap();
+        let json = serde_json::to_string(&key).unwrap();
+        assert_eq!(format!("\"{KEY}\""), json);
+        assert_eq!(key, serde_json::from_str(&json).unwrap());
+        let binary = bincode::serialize(&key).unwrap();
+        assert_eq!(key, bincode::deserialize(&binary).unwrap());
+
+        let id = FlickrId([0; 32]);
+        let json = serde_json::to_string(&id).unwrap();
+        assert_eq!(format!("\"{}\"", id), json);
+        assert_eq!(id, serde_json::from_str(&json).unwrap());
+    }
+}
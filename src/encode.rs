@@ -0,0 +1,137 @@
+use alloc::{string::String, vec::Vec};
+
+use crate::{
+    alphabet::Alphabet,
+    constants::{BYTE_COUNT_32, BYTE_COUNT_64, R1_DIV},
+    encode_fixed::encode_fixed,
+    scratch::Scratch,
+};
+
+pub(crate) fn encode(bytes: &[u8], alphabet: &Alphabet) -> String {
+    match bytes.len() {
+        BYTE_COUNT_32 => return encode_fixed::<32>(bytes.try_into().unwrap(), alphabet),
+        BYTE_COUNT_64 => return encode_fixed::<64>(bytes.try_into().unwrap(), alphabet),
+        _ => {}
+    }
+
+    /* Every leading zero byte becomes a leading '1', the rest is a
+    plain number. */
+    let in_leading_0s = bytes.iter().take_while(|&&b| b == 0).count();
+    let bytes = &bytes[in_leading_0s..];
+
+    /* Same idea as the fixed width conversion, but without the
+    tables: feed the number in one 32-bit limb at a time, most
+    significant first, and keep it in base 58^5.
+      X = sum_i intermediate[i] * 58^(5*i)
+    intermediate[i] < 58^5 and the carry is less than 2^32 + 2^3, so
+    intermediate[i] * 2^32 + carry fits comfortably in 64 bits. */
+    let mut intermediate: Scratch<Vec<u32>> =
+        Scratch::new(Vec::with_capacity(bytes.len() * 8 / 29 + 1));
+    let head = bytes.len() % 4;
+    let limbs = (head != 0)
+        .then(|| {
+            bytes[..head]
+                .iter()
+                .fold(0, |acc, &b| (acc << 8) | u32::from(b))
+        })
+        .into_iter()
+        .chain(
+            bytes[head..]
+                .chunks_exact(4)
+                .map(|chunk| u32::from_be_bytes(chunk.try_into().unwrap())),
+        );
+    for limb in limbs {
+        let mut carry = u64::from(limb);
+        for term in intermediate.iter_mut() {
+            let v = (u64::from(*term) << 32) + carry;
+            *term = (v % R1_DIV) as u32;
+            carry = v / R1_DIV;
+        }
+        while carry != 0 {
+            intermediate.push((carry % R1_DIV) as u32);
+            carry /= R1_DIV;
+        }
+    }
+
+    /* Convert intermediate form to base 58, most significant first.
+    Only the most significant term can have leading zeros to skip. */
+    let mut out: Vec<u8> = Vec::with_capacity(in_leading_0s + 5 * intermediate.len());
+    out.resize(in_leading_0s, alphabet.encode[0]);
+    for (i, &v) in intermediate.iter().rev().enumerate() {
+        let raw_base58 = [
+            v / 11_316_496,
+            v / 195_112 % 58,
+            v / 3_364 % 58,
+            v / 58 % 58,
+            v % 58,
+        ];
+        let skip = if i == 0 {
+            raw_base58.iter().take_while(|&&d| d == 0).count()
+        } else {
+            0
+        };
+        out.extend(
+            raw_base58[skip..]
+                .iter()
+                .map(|&d| alphabet.encode[d as usize]),
+        );
+    }
+
+    // SAFETY: every byte pushed comes from the ASCII base58 alphabet
+    unsafe { String::from_utf8_unchecked(out) }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::alphabet::Alphabet;
+
+    use super::encode;
+
+    #[test]
+    #[cfg_attr(miri, ignore)]
+    fn test_encode() {
+        let bitcoin = &Alphabet::BITCOIN;
+        assert_eq!(encode(&[], bitcoin), "");
+        assert_eq!(encode(&[0], bitcoin), "1");
+        assert_eq!(encode(&[0, 0, 0], bitcoin), "111");
+        assert_eq!(encode(&[57], bitcoin), "z");
+        assert_eq!(encode(&[58], bitcoin), "21");
+        assert_eq!(encode(b"Hello World!", bitcoin), "2NEpo7TZRRrLZSi2U");
+
+        for len in 0..200 {
+            for leading_0s in [0, 1, 5] {
+                let bytes: Vec<u8> = (0..len)
+                    .map(|i| {
+                        if i < leading_0s {
+                            0
+                        } else {
+                            (i * 89 + len) as u8
+                        }
+                    })
+                    .collect();
+                assert_eq!(encode(&bytes, bitcoin), bs58::encode(&bytes).into_string());
+            }
+            let bytes = vec![255; len];
+            assert_eq!(encode(&bytes, bitcoin), bs58::encode(&bytes).into_string());
+        }
+    }
+
+    #[test]
+    fn test_encode_alphabets() {
+        for len in [0, 1, 20, 25, 32, 64, 100] {
+            let bytes: Vec<u8> = (0..len).map(|i| if i < 2 { 0 } else { i as u8 }).collect();
+            assert_eq!(
+                encode(&bytes, &Alphabet::RIPPLE),
+                bs58::encode(&bytes)
+                    .with_alphabet(bs58::Alphabet::RIPPLE)
+                    .into_string()
+            );
+            assert_eq!(
+                encode(&bytes, &Alphabet::FLICKR),
+                bs58::encode(&bytes)
+                    .with_alphabet(bs58::Alphabet::FLICKR)
+                    .into_string()
+            );
+        }
+    }
+}
@@ -1,133 +1,169 @@
-use crate::constants::{
-    BASE58_CHARS, BINARY_SZ_32, BYTE_COUNT_32, ENC_TABLE_32, INTERMEDIATE_SZ_32, R1_DIV,
-    RAW58_SZ_32,
+use crate::{
+    alphabet::Alphabet,
+    constants::{
+        BASE58_ENCODED_32_LEN, BINARY_SZ_32, BYTE_COUNT_32, ENC_TABLE_32, INTERMEDIATE_SZ_32,
+        R1_DIV, RAW58_SZ_32,
+    },
+    encode_batch::encode_fixed_batch,
+    encode_fixed::encode_fixed_into,
+    scratch::Scratch,
+    string::Base58Inline,
 };
 
-pub(crate) fn encode_32<I: AsRef<[u8]>>(input: I) -> String {
-    let bytes: &[u8; 32] = input.as_ref().try_into().unwrap();
-    // Count leading zeros
-    let mut in_leading_0s = 0;
-    while in_leading_0s < BYTE_COUNT_32 {
-        if bytes[in_leading_0s] != 0 {
-            break;
-        }
-        in_leading_0s += 1;
-    }
+#[cfg(feature = "alloc")]
+use {crate::encode_fixed::encode_fixed, crate::Error, alloc::string::String};
 
-    /* X = sum_i bytes[i] * 2^(8*(BYTE_CNT-1-i)) */
+#[cfg(feature = "alloc")]
+pub(crate) fn try_encode_32<I: AsRef<[u8]>>(input: I) -> Result<String, Error> {
+    let input = input.as_ref();
+    let bytes: &[u8; BYTE_COUNT_32] = input.try_into().map_err(|_| Error::InvalidInputLength {
+        expected: BYTE_COUNT_32,
+        actual: input.len(),
+    })?;
+    Ok(encode_32(bytes))
+}
 
-    let mut binary: [u32; BINARY_SZ_32] = [0; BINARY_SZ_32];
-    let bytes_as_u32: &[u32] = unsafe {
-        // Cast a reference to bytes as a reference to u32
-        std::slice::from_raw_parts(
-            bytes.as_ptr() as *const u32,
-            bytes.len() / std::mem::size_of::<u32>(),
-        )
-    };
+#[cfg(feature = "alloc")]
+pub(crate) fn encode_32(bytes: &[u8; BYTE_COUNT_32]) -> String {
+    let mut out = String::with_capacity(BASE58_ENCODED_32_LEN as usize);
+    encode_32_into_string(bytes, &mut out);
+    out
+}
+
+#[cfg(feature = "alloc")]
+pub(crate) fn encode_32_with(bytes: &[u8; BYTE_COUNT_32], alphabet: &Alphabet) -> String {
+    encode_fixed(bytes, alphabet)
+}
 
-    /* Convert N to 32-bit limbs:
-    X = sum_i binary[i] * 2^(32*(BINARY_SZ-1-i)) */
+#[cfg(feature = "alloc")]
+pub(crate) fn encode_32_into_string(bytes: &[u8; BYTE_COUNT_32], out: &mut String) {
+    let mut buf = Scratch::new([0; BASE58_ENCODED_32_LEN as usize]);
+    let len = encode_32_into(bytes, &mut buf);
+    // SAFETY: every byte written by encode_32_into comes from the ASCII base58 alphabet
+    out.push_str(unsafe { core::str::from_utf8_unchecked(&buf[..len]) });
+}
+
+pub(crate) fn encode_32_inline(
+    bytes: &[u8; BYTE_COUNT_32],
+) -> Base58Inline<{ BASE58_ENCODED_32_LEN as usize }> {
+    let mut out = [0; BASE58_ENCODED_32_LEN as usize];
+    let len = encode_32_into(bytes, &mut out);
+    Base58Inline::from_encoded(out, len)
+}
 
-    for i in 0..BINARY_SZ_32 {
-        binary[i] = bytes_as_u32[i].to_be(); // Convert to big-endian (network byte order)
+pub(crate) fn encode_32_write<W: core::fmt::Write>(
+    bytes: &[u8; BYTE_COUNT_32],
+    out: &mut W,
+) -> core::fmt::Result {
+    let mut buf = Scratch::new([0; BASE58_ENCODED_32_LEN as usize]);
+    let len = encode_32_into(bytes, &mut buf);
+    // SAFETY: every byte written by encode_32_into comes from the ASCII base58 alphabet
+    out.write_str(unsafe { core::str::from_utf8_unchecked(&buf[..len]) })
+}
+
+pub(crate) fn encode_32_into(
+    bytes: &[u8; BYTE_COUNT_32],
+    out: &mut [u8; BASE58_ENCODED_32_LEN as usize],
+) -> usize {
+    #[cfg(all(target_arch = "x86_64", not(feature = "force-scalar")))]
+    if crate::encode_avx2::is_available() {
+        // SAFETY: AVX2 support was just checked
+        return unsafe { crate::encode_avx2::encode_fixed_into_avx2(bytes, out) };
+    }
+    #[cfg(all(target_arch = "aarch64", not(feature = "force-scalar")))]
+    if crate::encode_neon::is_available() {
+        // SAFETY: NEON support was just checked
+        return unsafe { crate::encode_neon::encode_fixed_into_neon(bytes, out) };
     }
+    encode_fixed_into(bytes, out, &Alphabet::BITCOIN)
+}
 
-    /* Convert to the intermediate format:
-      X = sum_i intermediate[i] * 58^(5*(INTERMEDIATE_SZ-1-i))
-    Initially, we don't require intermediate[i] < 58^5, but we do want
-    to make sure the sums don't overflow. */
+/// [`encode_32_into`] as a `const fn`, using the precomputed ENC_TABLE_32.  Returns the
+/// output buffer and how much of it is used.  See encode_fixed_into for the bounds.
+pub(crate) const fn encode_32_const(
+    bytes: &[u8; BYTE_COUNT_32],
+) -> ([u8; BASE58_ENCODED_32_LEN as usize], usize) {
+    let mut in_leading_0s = 0;
+    while in_leading_0s < BYTE_COUNT_32 && bytes[in_leading_0s] == 0 {
+        in_leading_0s += 1;
+    }
 
-    /* The worst case is if binary[7] is (2^32)-1. In that case
-    intermediate[8] will be be just over 2^63, which is fine. */
+    let mut binary = [0; BINARY_SZ_32];
+    let mut i = 0;
+    while i < BINARY_SZ_32 {
+        binary[i] = u32::from_be_bytes([
+            bytes[4 * i],
+            bytes[4 * i + 1],
+            bytes[4 * i + 2],
+            bytes[4 * i + 3],
+        ]) as u64;
+        i += 1;
+    }
 
-    let mut intermediate: [u64; INTERMEDIATE_SZ_32] = [0; INTERMEDIATE_SZ_32];
-    for i in 0..BINARY_SZ_32 {
-        for j in 0..INTERMEDIATE_SZ_32 - 1 {
-            intermediate[j + 1] += u64::from(binary[i]) * ENC_TABLE_32[i][j];
+    /* ENC_TABLE_32 leaves out the first term, which only ever gets carries */
+    let mut intermediate = [0; INTERMEDIATE_SZ_32];
+    let mut i = 0;
+    while i < BINARY_SZ_32 {
+        let mut j = 0;
+        while j < INTERMEDIATE_SZ_32 - 1 {
+            intermediate[j + 1] += binary[i] * ENC_TABLE_32[i][j];
+            j += 1;
         }
+        i += 1;
     }
 
-    /* Now we make sure each term is less than 58^5. Again, we have to be
-    a bit careful of overflow.
-    For N==32, in the worst case, as before, intermediate[8] will be
-    just over 2^63 and intermediate[7] will be just over 2^62.6.  In
-    the first step, we'll add floor(intermediate[8]/58^5) to
-    intermediate[7].  58^5 is pretty big though, so intermediate[7]
-    barely budges, and this is still fine.
-    For N==64, in the worst case, the biggest entry in intermediate at
-    this point is 2^63.87, and in the worst case, we add (2^64-1)/58^5,
-    which is still about 2^63.87. */
-
-    for i in (1..INTERMEDIATE_SZ_32).rev() {
+    let mut i = INTERMEDIATE_SZ_32 - 1;
+    while i > 0 {
         intermediate[i - 1] += intermediate[i] / R1_DIV;
         intermediate[i] %= R1_DIV;
+        i -= 1;
     }
 
-    /* Convert intermediate form to base 58.  This form of conversion
-    exposes tons of ILP, but it's more than the CPU can take advantage
-    of.
-      X = sum_i raw_base58[i] * 58^(RAW58_SZ-1-i) */
-
-    let mut raw_base58: [u8; RAW58_SZ_32] = [0; RAW58_SZ_32];
-
-    for i in 0..INTERMEDIATE_SZ_32 {
-        /* We know intermediate[ i ] < 58^5 < 2^32 for all i, so casting to
-        a uint is safe.  GCC doesn't seem to be able to realize this, so
-        when it converts ulong/ulong to a magic multiplication, it
-        generates the single-op 64b x 64b -> 128b mul instruction.  This
-        hurts the CPU's ability to take advantage of the ILP here. */
-        let v = intermediate[i] as u32;
-        raw_base58[5 * i + 4] = (v % 58) as u8;
-        raw_base58[5 * i + 3] = (v / 58 % 58) as u8;
-        raw_base58[5 * i + 2] = (v / 3364 % 58) as u8;
-        raw_base58[5 * i + 1] = (v / 195112 % 58) as u8;
-        raw_base58[5 * i] = (v / 11316496) as u8; // This one is known to be less than 58
+    let mut raw_base58 = [0; RAW58_SZ_32];
+    let mut i = 0;
+    while i < INTERMEDIATE_SZ_32 {
+        let mut v = intermediate[i] as u32;
+        let mut k = 5;
+        while k > 0 {
+            raw_base58[5 * i + k - 1] = (v % 58) as u8;
+            v /= 58;
+            k -= 1;
+        }
+        i += 1;
     }
 
-    /* Finally, actually convert to the string.  We have to ignore all the
-    leading zeros in raw_base58 and instead insert in_leading_0s
-    leading '1' characters.  We can show that raw_base58 actually has
-    at least in_leading_0s, so we'll do this by skipping the first few
-    leading zeros in raw_base58. */
-
     let mut raw_leading_0s = 0;
-    while raw_leading_0s < RAW58_SZ_32 {
-        if raw_base58[raw_leading_0s] != 0 {
-            break;
-        }
+    while raw_leading_0s < RAW58_SZ_32 && raw_base58[raw_leading_0s] == 0 {
         raw_leading_0s += 1;
     }
 
-    /* It's not immediately obvious that raw_leading_0s >= in_leading_0s,
-    but it's true.  In base b, X has floor(log_b X)+1 digits.  That
-    means in_leading_0s = N-1-floor(log_256 X) and raw_leading_0s =
-    RAW58_SZ-1-floor(log_58 X).  Let X<256^N be given and consider:
-    raw_leading_0s - in_leading_0s =
-      =  RAW58_SZ-N + floor( log_256 X ) - floor( log_58 X )
-      >= RAW58_SZ-N - 1 + ( log_256 X - log_58 X ) .
-    log_256 X - log_58 X is monotonically decreasing for X>0, so it
-    achieves it minimum at the maximum possible value for X, i.e.
-    256^N-1.
-      >= RAW58_SZ-N-1 + log_256(256^N-1) - log_58(256^N-1)
-    When N==32, RAW58_SZ is 45, so this gives skip >= 0.29
-    When N==64, RAW58_SZ is 90, so this gives skip >= 1.59.
-    Regardless, raw_leading_0s - in_leading_0s >= 0. */
-
-    let mut out = String::with_capacity(44);
-
     let skip = raw_leading_0s - in_leading_0s;
-    let end = RAW58_SZ_32 - skip;
-    for i in 0..end {
-        let idx = raw_base58[skip + i];
-        out.push(BASE58_CHARS[idx as usize]);
+    let len = RAW58_SZ_32 - skip;
+    let mut out = [0; BASE58_ENCODED_32_LEN as usize];
+    let mut i = 0;
+    while i < len {
+        out[i] = Alphabet::BITCOIN.encode[raw_base58[skip + i] as usize];
+        i += 1;
     }
+    (out, len)
+}
 
-    out
+pub(crate) fn encode_32_batch(
+    inputs: &[[u8; BYTE_COUNT_32]],
+    outputs: &mut [[u8; BASE58_ENCODED_32_LEN as usize]],
+    lens: &mut [u8],
+) {
+    encode_fixed_batch(inputs, outputs, lens)
 }
 
-#[cfg(test)]
+#[cfg(all(test, feature = "alloc"))]
 mod tests {
-    use super::encode_32;
+    use crate::{decode_32::decode_32, fixed::test_inputs, Error};
+
+    use super::{
+        encode_32, encode_32_const, encode_32_into, encode_32_into_string, encode_32_write,
+        try_encode_32,
+    };
 
     #[test]
     fn test_encode_32() {
@@ -150,4 +186,73 @@ mod tests {
             assert_eq!(result, key.to_string());
         }
     }
+
+    #[test]
+    fn test_encode_32_sinks() {
+        let bytes: [u8; 32] = bs58::decode("XkCriyrNwS3G4rzAXtG5B1nnvb5Ka1JtCku93VqeKAr")
+            .into_vec()
+            .unwrap()
+            .try_into()
+            .unwrap();
+        let expected = "XkCriyrNwS3G4rzAXtG5B1nnvb5Ka1JtCku93VqeKAr";
+
+        let mut buf = [0; 44];
+        let len = encode_32_into(&bytes, &mut buf);
+        assert_eq!(&buf[..len], expected.as_bytes());
+
+        let mut string = String::from("prefix:");
+        encode_32_into_string(&bytes, &mut string);
+        assert_eq!(string, format!("prefix:{expected}"));
+
+        let mut written = String::new();
+        encode_32_write(&bytes, &mut written).unwrap();
+        assert_eq!(written, expected);
+    }
+
+    #[test]
+    fn test_try_encode_32() {
+        assert_eq!(try_encode_32(vec![1; 32]), Ok(encode_32(&[1; 32])));
+        for len in [0, 1, 31, 33, 64] {
+            assert_eq!(
+                try_encode_32(vec![0; len]),
+                Err(Error::InvalidInputLength {
+                    expected: 32,
+                    actual: len
+                })
+            );
+        }
+    }
+
+    #[test]
+    fn test_misaligned_32() {
+        /* Every offset into a buffer aligned to 8, so the bytes and the
+        string start misaligned for u32 and u64 reads */
+        #[repr(align(8))]
+        struct Aligned([u8; 96]);
+        let key = "XkCriyrNwS3G4rzAXtG5B1nnvb5Ka1JtCku93VqeKAr";
+        let expected: [u8; 32] = bs58::decode(key).into_vec().unwrap().try_into().unwrap();
+        for offset in 0..8 {
+            let mut buf = Aligned([0; 96]);
+            buf.0[offset..offset + 32].copy_from_slice(&expected);
+            let bytes: &[u8; 32] = buf.0[offset..offset + 32].try_into().unwrap();
+            assert_eq!(encode_32(bytes), key);
+
+            buf.0[offset..offset + key.len()].copy_from_slice(key.as_bytes());
+            assert_eq!(decode_32(&buf.0[offset..offset + key.len()]), Ok(expected));
+        }
+    }
+
+    #[test]
+    fn test_encode_32_const() {
+        const ENCODED: ([u8; 44], usize) = encode_32_const(&[255; 32]);
+        assert_eq!(
+            b"JEKNVnkbo3jma5nREBBJCDoXFVeKkD56V3xKrvRmWxFG",
+            &ENCODED.0[..ENCODED.1]
+        );
+
+        for bytes in test_inputs::<32>(64, 34) {
+            let (out, len) = encode_32_const(&bytes);
+            assert_eq!(encode_32(&bytes).as_bytes(), &out[..len]);
+        }
+    }
 }
@@ -0,0 +1,297 @@
+use core::arch::x86_64::*;
+
+use crate::{
+    constants::R1_DIV,
+    fixed::{Fixed, MAX_BINARY_SZ, MAX_INTERMEDIATE_SZ},
+    scratch::Scratch,
+};
+
+/* Intermediate terms are kept 4 to a vector as u64s */
+const MAX_VECS: usize = MAX_INTERMEDIATE_SZ.div_ceil(4);
+
+/* Room for 8 byte stores of every term, rounded up to whole vectors */
+const RAW_BUF_SZ: usize = (5 * 4 * MAX_VECS + 3).div_ceil(32) * 32;
+
+struct Avx2<const N: usize>;
+
+impl<const N: usize> Avx2<N> {
+    const VECS: usize = Fixed::<N>::INTERMEDIATE_SZ.div_ceil(4);
+    /// ENC_TABLE with every row padded with zeros to a whole number of vectors
+    const ENC_TABLE: [[u64; 4 * MAX_VECS]; MAX_BINARY_SZ] = pad_rows(&Fixed::<N>::ENC_TABLE);
+}
+
+const fn pad_rows(
+    table: &[[u64; MAX_INTERMEDIATE_SZ]; MAX_BINARY_SZ],
+) -> [[u64; 4 * MAX_VECS]; MAX_BINARY_SZ] {
+    let mut out = [[0; 4 * MAX_VECS]; MAX_BINARY_SZ];
+    let mut i = 0;
+    while i < MAX_BINARY_SZ {
+        let mut j = 0;
+        while j < MAX_INTERMEDIATE_SZ {
+            out[i][j] = table[i][j];
+            j += 1;
+        }
+        i += 1;
+    }
+    out
+}
+
+/// Whether [`encode_fixed_into_avx2`] can run on this CPU.  Without `std`
+/// there is no runtime detection, so it has to be enabled at compile time.
+#[inline]
+pub(crate) fn is_available() -> bool {
+    #[cfg(feature = "std")]
+    {
+        std::is_x86_feature_detected!("avx2")
+    }
+    #[cfg(not(feature = "std"))]
+    {
+        cfg!(target_feature = "avx2")
+    }
+}
+
+/// x / 58 in every u64 lane, for x < 2^30.  2369637129 = ceil(2^37 / 58)
+/// and the rounding error stays below 1 for anything that small.
+#[target_feature(enable = "avx2")]
+#[inline]
+unsafe fn div58(x: __m256i) -> __m256i {
+    _mm256_srli_epi64::<37>(_mm256_mul_epu32(x, _mm256_set1_epi64x(2_369_637_129)))
+}
+
+/// Splits every u64 lane, which must be below 58^5, into 5 base58 digits,
+/// most significant first, stored in the 5 lowest bytes of the lane.
+#[target_feature(enable = "avx2")]
+#[inline]
+unsafe fn split_digits(x: __m256i) -> __m256i {
+    let fifty_eight = _mm256_set1_epi64x(58);
+    let q1 = div58(x);
+    let q2 = div58(q1);
+    let q3 = div58(q2);
+    let q4 = div58(q3);
+    let d4 = _mm256_sub_epi64(x, _mm256_mul_epu32(q1, fifty_eight));
+    let d3 = _mm256_sub_epi64(q1, _mm256_mul_epu32(q2, fifty_eight));
+    let d2 = _mm256_sub_epi64(q2, _mm256_mul_epu32(q3, fifty_eight));
+    let d1 = _mm256_sub_epi64(q3, _mm256_mul_epu32(q4, fifty_eight));
+    let d0 = q4;
+    let lo = _mm256_or_si256(d0, _mm256_slli_epi64::<8>(d1));
+    let mid = _mm256_or_si256(_mm256_slli_epi64::<16>(d2), _mm256_slli_epi64::<24>(d3));
+    _mm256_or_si256(_mm256_or_si256(lo, mid), _mm256_slli_epi64::<32>(d4))
+}
+
+/// Maps every byte, which must be a digit below 58, to its character in the
+/// Bitcoin alphabet: '1'..'9', 'A'..'H', 'J'..'N', 'P'..'Z', 'a'..'k', 'm'..'z'.
+/// Each gap in the alphabet is one more offset for the digits after it.
+#[target_feature(enable = "avx2")]
+#[inline]
+unsafe fn to_bitcoin_chars(d: __m256i) -> __m256i {
+    let mut c = _mm256_add_epi8(d, _mm256_set1_epi8(b'1' as i8));
+    let after_9 = _mm256_cmpgt_epi8(d, _mm256_set1_epi8(8));
+    c = _mm256_add_epi8(c, _mm256_and_si256(after_9, _mm256_set1_epi8(7)));
+    /* Comparisons give -1 for true, so subtracting them adds 1 */
+    c = _mm256_sub_epi8(c, _mm256_cmpgt_epi8(d, _mm256_set1_epi8(16)));
+    c = _mm256_sub_epi8(c, _mm256_cmpgt_epi8(d, _mm256_set1_epi8(21)));
+    let after_z = _mm256_cmpgt_epi8(d, _mm256_set1_epi8(32));
+    c = _mm256_add_epi8(c, _mm256_and_si256(after_z, _mm256_set1_epi8(6)));
+    _mm256_sub_epi8(c, _mm256_cmpgt_epi8(d, _mm256_set1_epi8(43)))
+}
+
+/// Bitmask of the bytes that are zero
+#[target_feature(enable = "avx2")]
+#[inline]
+unsafe fn zero_mask(chunk: &[u8]) -> u32 {
+    debug_assert!(chunk.len() >= 32);
+    let v = _mm256_loadu_si256(chunk.as_ptr().cast());
+    _mm256_movemask_epi8(_mm256_cmpeq_epi8(v, _mm256_setzero_si256())) as u32
+}
+
+/// [`encode_fixed_into`](crate::encode_fixed::encode_fixed_into) with the Bitcoin
+/// alphabet.  The table multiply, the split into digits and the alphabet lookup
+/// work on whole vectors, only the carries between terms are left scalar since
+/// there are no vector divides.
+///
+/// # Safety
+///
+/// The CPU must support AVX2, see [`is_available`].
+#[target_feature(enable = "avx2")]
+pub(crate) unsafe fn encode_fixed_into_avx2<const N: usize>(
+    bytes: &[u8; N],
+    out: &mut [u8],
+) -> usize {
+    let () = Fixed::<N>::VALID;
+    let binary_sz = Fixed::<N>::BINARY_SZ;
+    let intermediate_sz = Fixed::<N>::INTERMEDIATE_SZ;
+    let raw58_sz = Fixed::<N>::RAW58_SZ;
+    let vecs = Avx2::<N>::VECS;
+    assert!(out.len() >= Fixed::<N>::ENCODED_LEN);
+
+    let mut in_leading_0s = 0;
+    for chunk in bytes.chunks_exact(32) {
+        let zeros = zero_mask(chunk);
+        in_leading_0s += zeros.trailing_ones() as usize;
+        if zeros != u32::MAX {
+            break;
+        }
+    }
+    if in_leading_0s == N / 32 * 32 {
+        in_leading_0s += bytes[in_leading_0s..]
+            .iter()
+            .take_while(|&&b| b == 0)
+            .count();
+    }
+
+    let mut binary: Scratch<[u32; MAX_BINARY_SZ]> = Scratch::new([0; MAX_BINARY_SZ]);
+    let head = N % 4;
+    for &byte in &bytes[..head] {
+        binary[0] = (binary[0] << 8) | u32::from(byte);
+    }
+    let full_limbs = &mut binary[usize::from(head != 0)..binary_sz];
+    for (limb, chunk) in full_limbs.iter_mut().zip(bytes[head..].chunks_exact(4)) {
+        *limb = u32::from_be_bytes(chunk.try_into().unwrap());
+    }
+
+    /* Every limb and table entry is below 2^32, so _mm256_mul_epu32
+    gives the same products as the scalar code.  The overflow bounds
+    and reduction schedule are the same too. */
+    let table = &Avx2::<N>::ENC_TABLE;
+    let first = Fixed::<N>::ENC_START / 4;
+    let mut acc: Scratch<[__m256i; MAX_VECS]> = Scratch::new([_mm256_setzero_si256(); MAX_VECS]);
+    let mut intermediate: Scratch<[u64; 4 * MAX_VECS]> = Scratch::new([0; 4 * MAX_VECS]);
+    let mut start = 0;
+    while start < binary_sz {
+        let end = Fixed::<N>::ENC_SEGMENT_END[start];
+        for i in start..end {
+            let limb = _mm256_set1_epi64x(i64::from(binary[i]));
+            for v in first..vecs {
+                let row = _mm256_loadu_si256(table[i][4 * v..].as_ptr().cast());
+                acc[v] = _mm256_add_epi64(acc[v], _mm256_mul_epu32(limb, row));
+            }
+        }
+        if end < binary_sz {
+            /* Mini-reduction */
+            let reduce = Fixed::<N>::ENC_REDUCE[end];
+            for v in 0..vecs {
+                _mm256_storeu_si256(intermediate[4 * v..].as_mut_ptr().cast(), acc[v]);
+            }
+            for j in (1..intermediate_sz).rev() {
+                if reduce & (1 << j) != 0 {
+                    intermediate[j - 1] += intermediate[j] / R1_DIV;
+                    intermediate[j] %= R1_DIV;
+                }
+            }
+            for v in 0..vecs {
+                acc[v] = _mm256_loadu_si256(intermediate[4 * v..].as_ptr().cast());
+            }
+        }
+        start = end;
+    }
+    for v in 0..vecs {
+        _mm256_storeu_si256(intermediate[4 * v..].as_mut_ptr().cast(), acc[v]);
+    }
+
+    for i in (1..intermediate_sz).rev() {
+        intermediate[i - 1] += intermediate[i] / R1_DIV;
+        intermediate[i] %= R1_DIV;
+    }
+
+    /* Every term becomes 5 digits in the low bytes of its lane.  Storing
+    all 8 bytes of each lane in order lets the next term overwrite the
+    3 high zero bytes.  Padding terms are 0 and land past RAW58_SZ. */
+    let mut raw_base58: Scratch<[u8; RAW_BUF_SZ]> = Scratch::new([0; RAW_BUF_SZ]);
+    for v in 0..vecs {
+        let terms = _mm256_loadu_si256(intermediate[4 * v..].as_ptr().cast());
+        let mut digits: Scratch<[u64; 4]> = Scratch::new([0; 4]);
+        _mm256_storeu_si256(digits.as_mut_ptr().cast(), split_digits(terms));
+        for (k, digits) in digits.iter().enumerate() {
+            let t = 4 * v + k;
+            raw_base58[5 * t..5 * t + 8].copy_from_slice(&digits.to_le_bytes());
+        }
+    }
+
+    let mut raw_leading_0s = 0;
+    for chunk in raw_base58.chunks_exact(32) {
+        let zeros = zero_mask(chunk);
+        raw_leading_0s += zeros.trailing_ones() as usize;
+        if zeros != u32::MAX {
+            break;
+        }
+    }
+    let raw_leading_0s = raw_leading_0s.min(raw58_sz);
+
+    /* See encode_fixed_into for why this can't underflow */
+    let skip = raw_leading_0s - in_leading_0s;
+    let end = raw58_sz - skip;
+
+    let mut chars: Scratch<[u8; RAW_BUF_SZ]> = Scratch::new([0; RAW_BUF_SZ]);
+    let chunks = raw58_sz.div_ceil(32);
+    for (digits, chars) in raw_base58
+        .chunks_exact(32)
+        .zip(chars.chunks_exact_mut(32))
+        .take(chunks)
+    {
+        let digits = _mm256_loadu_si256(digits.as_ptr().cast());
+        _mm256_storeu_si256(chars.as_mut_ptr().cast(), to_bitcoin_chars(digits));
+    }
+    out[..end].copy_from_slice(&chars[skip..raw58_sz]);
+
+    end
+}
+
+#[cfg(test)]
+mod tests {
+    use core::arch::x86_64::*;
+
+    use crate::{
+        alphabet::Alphabet,
+        fixed::{check_encoder, for_each_test_width},
+    };
+
+    use super::{encode_fixed_into_avx2, is_available, split_digits, to_bitcoin_chars};
+
+    fn check<const N: usize>() {
+        // SAFETY: the callers checked is_available
+        check_encoder::<N>(|bytes, out| unsafe { encode_fixed_into_avx2(bytes, out) });
+    }
+
+    #[test]
+    #[cfg_attr(miri, ignore)]
+    fn test_encode_avx2() {
+        if !is_available() {
+            return;
+        }
+        for_each_test_width!(check);
+    }
+
+    #[test]
+    #[cfg_attr(miri, ignore)]
+    fn test_helpers() {
+        if !is_available() {
+            return;
+        }
+        // SAFETY: AVX2 support was just checked
+        unsafe {
+            let digits: [u8; 64] = core::array::from_fn(|i| (i % 58) as u8);
+            let mut chars = [0; 64];
+            for (digits, chars) in digits.chunks_exact(32).zip(chars.chunks_exact_mut(32)) {
+                let v = to_bitcoin_chars(_mm256_loadu_si256(digits.as_ptr().cast()));
+                _mm256_storeu_si256(chars.as_mut_ptr().cast(), v);
+            }
+            for (digit, char) in digits.iter().zip(chars) {
+                assert_eq!(Alphabet::BITCOIN.encode[*digit as usize], char);
+            }
+
+            let terms: [u64; 4] = [0, 57, 58 * 58 * 58 * 58 * 58 - 1, 123_456_789];
+            let mut out = [0u64; 4];
+            let v = split_digits(_mm256_loadu_si256(terms.as_ptr().cast()));
+            _mm256_storeu_si256(out.as_mut_ptr().cast(), v);
+            for (term, digits) in terms.iter().zip(out) {
+                let digits = digits.to_le_bytes();
+                let value = digits[..5]
+                    .iter()
+                    .fold(0, |acc, &d| acc * 58 + u64::from(d));
+                assert_eq!(value, *term);
+                assert!(digits[..5].iter().all(|&d| d < 58));
+                assert_eq!(digits[5..], [0; 3]);
+            }
+        }
+    }
+}
@@ -0,0 +1,54 @@
+use crate::{alphabet::Alphabet, encode_fixed::encode_fixed_into_fast, fixed::Fixed};
+
+/// Encodes every input into the matching output and stores its length in `lens`.  This is
+/// only a convenience: each input is encoded on its own, like
+/// [`encode_32_into`](crate::encode_32_into), with no work shared between them.
+pub(crate) fn encode_fixed_batch<const N: usize, const L: usize>(
+    inputs: &[[u8; N]],
+    outputs: &mut [[u8; L]],
+    lens: &mut [u8],
+) {
+    let () = Fixed::<N>::VALID;
+    assert!(L >= Fixed::<N>::ENCODED_LEN);
+    assert!(outputs.len() >= inputs.len() && lens.len() >= inputs.len());
+
+    for ((input, output), len) in inputs.iter().zip(outputs).zip(lens) {
+        *len = encode_fixed_into_fast(input, output, &Alphabet::BITCOIN) as u8;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{alphabet::Alphabet, encode_fixed::encode_fixed_into, fixed::test_inputs};
+
+    use super::encode_fixed_batch;
+
+    fn check<const N: usize, const L: usize>() {
+        let inputs = test_inputs::<N>(16, 4);
+        let mut outputs = vec![[0; L]; inputs.len()];
+        let mut lens = vec![0; inputs.len()];
+        encode_fixed_batch(&inputs, &mut outputs, &mut lens);
+
+        for ((input, output), len) in inputs.iter().zip(&outputs).zip(&lens) {
+            let mut expected = [0; L];
+            let expected_len = encode_fixed_into(input, &mut expected, &Alphabet::BITCOIN);
+            assert_eq!(&output[..*len as usize], &expected[..expected_len]);
+        }
+
+        encode_fixed_batch::<N, L>(&[], &mut [], &mut []);
+    }
+
+    #[test]
+    fn test_encode_fixed_batch() {
+        check::<32, 44>();
+        check::<64, 88>();
+        check::<33, 46>();
+        check::<128, 175>();
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_short_outputs() {
+        encode_fixed_batch(&[[0u8; 32]; 2], &mut [[0; 44]; 1], &mut [0; 2]);
+    }
+}
@@ -0,0 +1,237 @@
+use crate::{
+    alphabet::Alphabet,
+    constants::{
+        BASE58_ENCODED_32_LEN, BASE58_ENCODED_64_LEN, BYTE_COUNT_32, BYTE_COUNT_64, R1_DIV,
+    },
+    encode_32::encode_32_into,
+    encode_64::encode_64_into,
+    fixed::{Fixed, MAX_BINARY_SZ, MAX_INTERMEDIATE_SZ, MAX_RAW58_SZ},
+    scratch::{init_prefix, Scratch},
+};
+use core::mem::MaybeUninit;
+
+#[cfg(feature = "alloc")]
+use {crate::fixed::MAX_ENCODED_LEN, alloc::string::String};
+
+#[cfg(feature = "alloc")]
+pub(crate) fn encode_fixed<const N: usize>(bytes: &[u8; N], alphabet: &Alphabet) -> String {
+    let mut buf = Scratch::new([0; MAX_ENCODED_LEN]);
+    let len = encode_fixed_into_fast(bytes, &mut buf[..], alphabet);
+    // SAFETY: every byte written by the encoders comes from an ASCII base58 alphabet
+    String::from(unsafe { core::str::from_utf8_unchecked(&buf[..len]) })
+}
+
+/// [`encode_fixed_into`], going through the optimized [`encode_32_into`] and
+/// [`encode_64_into`] when `N` is 32 or 64 and `alphabet` is the Bitcoin one, which is
+/// the only one the SIMD versions handle.
+pub(crate) fn encode_fixed_into_fast<const N: usize>(
+    bytes: &[u8; N],
+    out: &mut [u8],
+    alphabet: &Alphabet,
+) -> usize {
+    if alphabet.encode == Alphabet::BITCOIN.encode {
+        if N == BYTE_COUNT_32 {
+            return encode_32_into(
+                bytes[..].try_into().unwrap(),
+                (&mut out[..BASE58_ENCODED_32_LEN as usize])
+                    .try_into()
+                    .unwrap(),
+            );
+        }
+        if N == BYTE_COUNT_64 {
+            return encode_64_into(
+                bytes[..].try_into().unwrap(),
+                (&mut out[..BASE58_ENCODED_64_LEN as usize])
+                    .try_into()
+                    .unwrap(),
+            );
+        }
+    }
+    encode_fixed_into(bytes, out, alphabet)
+}
+
+/// Writes the base58 encoding of `bytes` to the start of `out` and returns its length.
+/// `out` must be at least `Fixed::<N>::ENCODED_LEN` bytes long.
+#[allow(clippy::needless_range_loop)]
+pub(crate) fn encode_fixed_into<const N: usize>(
+    bytes: &[u8; N],
+    out: &mut [u8],
+    alphabet: &Alphabet,
+) -> usize {
+    let () = Fixed::<N>::VALID;
+    let binary_sz = Fixed::<N>::BINARY_SZ;
+    let intermediate_sz = Fixed::<N>::INTERMEDIATE_SZ;
+    let raw58_sz = Fixed::<N>::RAW58_SZ;
+    assert!(out.len() >= Fixed::<N>::ENCODED_LEN);
+
+    // Count leading zeros
+    let mut in_leading_0s = 0;
+    while in_leading_0s < N {
+        if bytes[in_leading_0s] != 0 {
+            break;
+        }
+        in_leading_0s += 1;
+    }
+
+    /* X = sum_i bytes[i] * 2^(8*(BYTE_CNT-1-i)) */
+
+    let mut binary_buf = Scratch::new([MaybeUninit::uninit(); MAX_BINARY_SZ]);
+    let binary = init_prefix(&mut binary_buf[..], binary_sz, 0u32);
+
+    /* Convert N to 32-bit limbs:
+    X = sum_i binary[i] * 2^(32*(BINARY_SZ-1-i))
+    When N isn't a multiple of 4, binary[0] only holds the first
+    HEAD_BYTES bytes. */
+
+    let head = N % 4;
+    for &byte in &bytes[..head] {
+        binary[0] = (binary[0] << 8) | u32::from(byte);
+    }
+    let full_limbs = &mut binary[usize::from(head != 0)..];
+    for (limb, chunk) in full_limbs.iter_mut().zip(bytes[head..].chunks_exact(4)) {
+        *limb = u32::from_be_bytes(chunk.try_into().unwrap());
+    }
+
+    /* Convert to the intermediate format:
+      X = sum_i intermediate[i] * 58^(5*(INTERMEDIATE_SZ-1-i))
+    Initially, we don't require intermediate[i] < 58^5, but we do want
+    to make sure the sums don't overflow.
+
+    For N==32 the worst case is if binary[7] is (2^32)-1. In that case
+    intermediate[8] will be be just over 2^63, which is fine.  For
+    larger N, e.g. N==64, intermediate[16] could overflow if we did it
+    the same way, so terms are mini-reduced whenever the next limb
+    could push them over.  ENC_REDUCE is worked out at compile time
+    from the worst case input.  For N==64 it's a single reduction of
+    intermediate[16] after the first 9 limbs. */
+
+    let mut intermediate_buf = Scratch::new([MaybeUninit::uninit(); MAX_INTERMEDIATE_SZ]);
+    let intermediate = init_prefix(&mut intermediate_buf[..], intermediate_sz, 0u64);
+    let mut start = 0;
+    while start < binary_sz {
+        let end = Fixed::<N>::ENC_SEGMENT_END[start];
+        for i in start..end {
+            for j in Fixed::<N>::ENC_START..intermediate_sz {
+                intermediate[j] += u64::from(binary[i]) * Fixed::<N>::ENC_TABLE[i][j];
+            }
+        }
+        if end < binary_sz {
+            /* Mini-reduction */
+            let reduce = Fixed::<N>::ENC_REDUCE[end];
+            for j in (1..intermediate_sz).rev() {
+                if reduce & (1 << j) != 0 {
+                    intermediate[j - 1] += intermediate[j] / R1_DIV;
+                    intermediate[j] %= R1_DIV;
+                }
+            }
+        }
+        start = end;
+    }
+
+    /* Now we make sure each term is less than 58^5. Again, we have to be
+    a bit careful of overflow.
+    For N==32, in the worst case, as before, intermediate[8] will be
+    just over 2^63 and intermediate[7] will be just over 2^62.6.  In
+    the first step, we'll add floor(intermediate[8]/58^5) to
+    intermediate[7].  58^5 is pretty big though, so intermediate[7]
+    barely budges, and this is still fine.
+    For N==64, in the worst case, the biggest entry in intermediate at
+    this point is 2^63.87, and in the worst case, we add (2^64-1)/58^5,
+    which is still about 2^63.87.  The same bound is checked for every
+    other N when ENC_REDUCE is computed. */
+
+    for i in (1..intermediate_sz).rev() {
+        intermediate[i - 1] += intermediate[i] / R1_DIV;
+        intermediate[i] %= R1_DIV;
+    }
+
+    /* Convert intermediate form to base 58.  This form of conversion
+    exposes tons of ILP, but it's more than the CPU can take advantage
+    of.
+      X = sum_i raw_base58[i] * 58^(RAW58_SZ-1-i) */
+
+    let mut raw_base58_buf = Scratch::new([MaybeUninit::uninit(); MAX_RAW58_SZ]);
+    let raw_base58 = init_prefix(&mut raw_base58_buf[..], raw58_sz, 0u8);
+
+    for i in 0..intermediate_sz {
+        /* We know intermediate[ i ] < 58^5 < 2^32 for all i, so casting to
+        a uint is safe.  GCC doesn't seem to be able to realize this, so
+        when it converts ulong/ulong to a magic multiplication, it
+        generates the single-op 64b x 64b -> 128b mul instruction.  This
+        hurts the CPU's ability to take advantage of the ILP here. */
+        let v = intermediate[i] as u32;
+        raw_base58[5 * i + 4] = (v % 58) as u8;
+        raw_base58[5 * i + 3] = (v / 58 % 58) as u8;
+        raw_base58[5 * i + 2] = (v / 3364 % 58) as u8;
+        raw_base58[5 * i + 1] = (v / 195112 % 58) as u8;
+        raw_base58[5 * i] = (v / 11316496) as u8; // This one is known to be less than 58
+    }
+
+    /* Finally, actually convert to the string.  We have to ignore all the
+    leading zeros in raw_base58 and instead insert in_leading_0s
+    leading '1' characters.  We can show that raw_base58 actually has
+    at least in_leading_0s, so we'll do this by skipping the first few
+    leading zeros in raw_base58. */
+
+    let mut raw_leading_0s = 0;
+    while raw_leading_0s < raw58_sz {
+        if raw_base58[raw_leading_0s] != 0 {
+            break;
+        }
+        raw_leading_0s += 1;
+    }
+
+    /* It's not immediately obvious that raw_leading_0s >= in_leading_0s,
+    but it's true.  In base b, X has floor(log_b X)+1 digits.  That
+    means in_leading_0s = N-1-floor(log_256 X) and raw_leading_0s =
+    RAW58_SZ-1-floor(log_58 X).  Let X<256^N be given and consider:
+    raw_leading_0s - in_leading_0s =
+      =  RAW58_SZ-N + floor( log_256 X ) - floor( log_58 X )
+      >= RAW58_SZ-N - 1 + ( log_256 X - log_58 X ) .
+    log_256 X - log_58 X is monotonically decreasing for X>0, so it
+    achieves it minimum at the maximum possible value for X, i.e.
+    256^N-1.
+      >= RAW58_SZ-N-1 + log_256(256^N-1) - log_58(256^N-1)
+    When N==32, RAW58_SZ is 45, so this gives skip >= 0.29
+    When N==64, RAW58_SZ is 90, so this gives skip >= 1.59.
+    Regardless, raw_leading_0s - in_leading_0s >= 0. */
+
+    let skip = raw_leading_0s - in_leading_0s;
+    let end = raw58_sz - skip;
+    for i in 0..end {
+        let idx = raw_base58[skip + i];
+        out[i] = alphabet.encode[idx as usize];
+    }
+
+    end
+}
+
+#[cfg(all(test, feature = "alloc"))]
+mod tests {
+    use crate::{
+        alphabet::Alphabet,
+        fixed::{for_each_test_width, test_inputs},
+    };
+
+    use super::encode_fixed;
+
+    fn check<const N: usize>() {
+        for bytes in test_inputs::<N>(16, 4) {
+            assert_eq!(
+                encode_fixed(&bytes, &Alphabet::BITCOIN),
+                bs58::encode(bytes).into_string()
+            );
+            assert_eq!(
+                encode_fixed(&bytes, &Alphabet::RIPPLE),
+                bs58::encode(bytes)
+                    .with_alphabet(bs58::Alphabet::RIPPLE)
+                    .into_string()
+            );
+        }
+    }
+
+    #[test]
+    fn test_encode_fixed() {
+        for_each_test_width!(check);
+    }
+}
@@ -0,0 +1,316 @@
+use core::arch::aarch64::*;
+
+use crate::{
+    alphabet::Alphabet,
+    constants::R1_DIV,
+    fixed::{Fixed, MAX_BINARY_SZ, MAX_INTERMEDIATE_SZ},
+    scratch::Scratch,
+};
+
+/* Intermediate terms are kept 2 to a vector as u64s */
+const MAX_VECS: usize = MAX_INTERMEDIATE_SZ.div_ceil(2);
+
+/* Room for 8 byte stores of every term, rounded up to whole vectors */
+const RAW_BUF_SZ: usize = (5 * 2 * MAX_VECS + 3).div_ceil(16) * 16;
+
+/* The Bitcoin alphabet padded to the 64 bytes a 4 register lookup takes */
+const BITCOIN_CHARS: [u8; 64] = pad_alphabet(Alphabet::BITCOIN.as_bytes());
+
+struct Neon<const N: usize>;
+
+impl<const N: usize> Neon<N> {
+    const VECS: usize = Fixed::<N>::INTERMEDIATE_SZ.div_ceil(2);
+    /// ENC_TABLE as u32s, with every row padded with zeros to a whole number of vectors
+    const ENC_TABLE: [[u32; 2 * MAX_VECS]; MAX_BINARY_SZ] = narrow_rows(&Fixed::<N>::ENC_TABLE);
+}
+
+const fn narrow_rows(
+    table: &[[u64; MAX_INTERMEDIATE_SZ]; MAX_BINARY_SZ],
+) -> [[u32; 2 * MAX_VECS]; MAX_BINARY_SZ] {
+    let mut out = [[0; 2 * MAX_VECS]; MAX_BINARY_SZ];
+    let mut i = 0;
+    while i < MAX_BINARY_SZ {
+        let mut j = 0;
+        while j < MAX_INTERMEDIATE_SZ {
+            /* Every entry is below 58^5 */
+            out[i][j] = table[i][j] as u32;
+            j += 1;
+        }
+        i += 1;
+    }
+    out
+}
+
+const fn pad_alphabet(chars: &[u8; 58]) -> [u8; 64] {
+    let mut out = [0; 64];
+    let mut i = 0;
+    while i < 58 {
+        out[i] = chars[i];
+        i += 1;
+    }
+    out
+}
+
+/// Whether the NEON encoder and decoder can run on this CPU.  Without `std`
+/// there is no runtime detection, so it has to be enabled at compile time.
+#[inline]
+pub(crate) fn is_available() -> bool {
+    #[cfg(feature = "std")]
+    {
+        std::arch::is_aarch64_feature_detected!("neon")
+    }
+    #[cfg(not(feature = "std"))]
+    {
+        cfg!(target_feature = "neon")
+    }
+}
+
+/// Packs a comparison result, 0xFF or 0 in every byte, into 4 bits per byte
+/// since there is no movemask.  Byte i ends up in bits 4*i..4*i+4.
+#[target_feature(enable = "neon")]
+#[inline]
+pub(crate) unsafe fn nibble_mask(mask: uint8x16_t) -> u64 {
+    let narrowed = vshrn_n_u16::<4>(vreinterpretq_u16_u8(mask));
+    vget_lane_u64::<0>(vreinterpret_u64_u8(narrowed))
+}
+
+/// x / 58 in every u64 lane, for x < 2^30.  2369637129 = ceil(2^37 / 58)
+/// and the rounding error stays below 1 for anything that small.
+#[target_feature(enable = "neon")]
+#[inline]
+unsafe fn div58(x: uint64x2_t) -> uint64x2_t {
+    vshrq_n_u64::<37>(vmull_u32(vmovn_u64(x), vdup_n_u32(2_369_637_129)))
+}
+
+/// x - 58 * q in every u64 lane
+#[target_feature(enable = "neon")]
+#[inline]
+unsafe fn rem58(x: uint64x2_t, q: uint64x2_t) -> uint64x2_t {
+    vsubq_u64(x, vmull_u32(vmovn_u64(q), vdup_n_u32(58)))
+}
+
+/// Splits every u64 lane, which must be below 58^5, into 5 base58 digits,
+/// most significant first, stored in the 5 lowest bytes of the lane.
+#[target_feature(enable = "neon")]
+#[inline]
+unsafe fn split_digits(x: uint64x2_t) -> uint64x2_t {
+    let q1 = div58(x);
+    let q2 = div58(q1);
+    let q3 = div58(q2);
+    let q4 = div58(q3);
+    let d4 = rem58(x, q1);
+    let d3 = rem58(q1, q2);
+    let d2 = rem58(q2, q3);
+    let d1 = rem58(q3, q4);
+    let d0 = q4;
+    let lo = vorrq_u64(d0, vshlq_n_u64::<8>(d1));
+    let mid = vorrq_u64(vshlq_n_u64::<16>(d2), vshlq_n_u64::<24>(d3));
+    vorrq_u64(vorrq_u64(lo, mid), vshlq_n_u64::<32>(d4))
+}
+
+/// Maps every byte, which must be a digit below 58, to its character in the
+/// Bitcoin alphabet with a single 64 byte table lookup.
+#[target_feature(enable = "neon")]
+#[inline]
+unsafe fn to_bitcoin_chars(d: uint8x16_t) -> uint8x16_t {
+    vqtbl4q_u8(vld1q_u8_x4(BITCOIN_CHARS.as_ptr()), d)
+}
+
+/// Number of leading zero bytes in `bytes`
+#[target_feature(enable = "neon")]
+#[inline]
+unsafe fn leading_zeros(bytes: &[u8]) -> usize {
+    let mut cnt = 0;
+    let mut chunks = bytes.chunks_exact(16);
+    for chunk in &mut chunks {
+        let zeros = nibble_mask(vceqzq_u8(vld1q_u8(chunk.as_ptr())));
+        cnt += zeros.trailing_ones() as usize / 4;
+        if zeros != u64::MAX {
+            return cnt;
+        }
+    }
+    cnt + chunks.remainder().iter().take_while(|&&b| b == 0).count()
+}
+
+/// [`encode_fixed_into`](crate::encode_fixed::encode_fixed_into) with the Bitcoin
+/// alphabet.  Same layout as the AVX2 version with 2 terms to a vector instead
+/// of 4: the table multiply, the split into digits and the alphabet lookup are
+/// vectorized, the carries between terms are left scalar.
+///
+/// # Safety
+///
+/// The CPU must support NEON, see [`is_available`].
+#[target_feature(enable = "neon")]
+pub(crate) unsafe fn encode_fixed_into_neon<const N: usize>(
+    bytes: &[u8; N],
+    out: &mut [u8],
+) -> usize {
+    let () = Fixed::<N>::VALID;
+    let binary_sz = Fixed::<N>::BINARY_SZ;
+    let intermediate_sz = Fixed::<N>::INTERMEDIATE_SZ;
+    let raw58_sz = Fixed::<N>::RAW58_SZ;
+    let vecs = Neon::<N>::VECS;
+    assert!(out.len() >= Fixed::<N>::ENCODED_LEN);
+
+    let in_leading_0s = leading_zeros(bytes);
+
+    let mut binary: Scratch<[u32; MAX_BINARY_SZ]> = Scratch::new([0; MAX_BINARY_SZ]);
+    let head = N % 4;
+    for &byte in &bytes[..head] {
+        binary[0] = (binary[0] << 8) | u32::from(byte);
+    }
+    let full_limbs = &mut binary[usize::from(head != 0)..binary_sz];
+    for (limb, chunk) in full_limbs.iter_mut().zip(bytes[head..].chunks_exact(4)) {
+        *limb = u32::from_be_bytes(chunk.try_into().unwrap());
+    }
+
+    /* vmlal_u32 multiplies u32s into u64s, which gives the same
+    products as the scalar code.  The overflow bounds and reduction
+    schedule are the same too. */
+    let table = &Neon::<N>::ENC_TABLE;
+    let first = Fixed::<N>::ENC_START / 2;
+    let mut acc: Scratch<[uint64x2_t; MAX_VECS]> = Scratch::new([vdupq_n_u64(0); MAX_VECS]);
+    let mut intermediate: Scratch<[u64; 2 * MAX_VECS]> = Scratch::new([0; 2 * MAX_VECS]);
+    let mut start = 0;
+    while start < binary_sz {
+        let end = Fixed::<N>::ENC_SEGMENT_END[start];
+        for i in start..end {
+            let limb = vdup_n_u32(binary[i]);
+            for v in first..vecs {
+                let row = vld1_u32(table[i][2 * v..].as_ptr());
+                acc[v] = vmlal_u32(acc[v], limb, row);
+            }
+        }
+        if end < binary_sz {
+            /* Mini-reduction */
+            let reduce = Fixed::<N>::ENC_REDUCE[end];
+            for v in 0..vecs {
+                vst1q_u64(intermediate[2 * v..].as_mut_ptr(), acc[v]);
+            }
+            for j in (1..intermediate_sz).rev() {
+                if reduce & (1 << j) != 0 {
+                    intermediate[j - 1] += intermediate[j] / R1_DIV;
+                    intermediate[j] %= R1_DIV;
+                }
+            }
+            for v in 0..vecs {
+                acc[v] = vld1q_u64(intermediate[2 * v..].as_ptr());
+            }
+        }
+        start = end;
+    }
+    for v in 0..vecs {
+        vst1q_u64(intermediate[2 * v..].as_mut_ptr(), acc[v]);
+    }
+
+    for i in (1..intermediate_sz).rev() {
+        intermediate[i - 1] += intermediate[i] / R1_DIV;
+        intermediate[i] %= R1_DIV;
+    }
+
+    /* Every term becomes 5 digits in the low bytes of its lane.  Storing
+    all 8 bytes of each lane in order lets the next term overwrite the
+    3 high zero bytes.  Padding terms are 0 and land past RAW58_SZ. */
+    let mut raw_base58: Scratch<[u8; RAW_BUF_SZ]> = Scratch::new([0; RAW_BUF_SZ]);
+    for v in 0..vecs {
+        let terms = vld1q_u64(intermediate[2 * v..].as_ptr());
+        let mut digits: Scratch<[u64; 2]> = Scratch::new([0; 2]);
+        vst1q_u64(digits.as_mut_ptr(), split_digits(terms));
+        for (k, digits) in digits.iter().enumerate() {
+            let t = 2 * v + k;
+            raw_base58[5 * t..5 * t + 8].copy_from_slice(&digits.to_le_bytes());
+        }
+    }
+
+    let raw_leading_0s = leading_zeros(&raw_base58[..]).min(raw58_sz);
+
+    /* See encode_fixed_into for why this can't underflow */
+    let skip = raw_leading_0s - in_leading_0s;
+    let end = raw58_sz - skip;
+
+    let mut chars: Scratch<[u8; RAW_BUF_SZ]> = Scratch::new([0; RAW_BUF_SZ]);
+    let chunks = raw58_sz.div_ceil(16);
+    for (digits, chars) in raw_base58
+        .chunks_exact(16)
+        .zip(chars.chunks_exact_mut(16))
+        .take(chunks)
+    {
+        let digits = vld1q_u8(digits.as_ptr());
+        vst1q_u8(chars.as_mut_ptr(), to_bitcoin_chars(digits));
+    }
+    out[..end].copy_from_slice(&chars[skip..raw58_sz]);
+
+    end
+}
+
+#[cfg(test)]
+mod tests {
+    use core::arch::aarch64::*;
+
+    use crate::{
+        alphabet::Alphabet,
+        fixed::{check_encoder, for_each_test_width},
+    };
+
+    use super::{
+        encode_fixed_into_neon, is_available, leading_zeros, split_digits, to_bitcoin_chars,
+    };
+
+    fn check<const N: usize>() {
+        // SAFETY: the callers checked is_available
+        check_encoder::<N>(|bytes, out| unsafe { encode_fixed_into_neon(bytes, out) });
+    }
+
+    #[test]
+    #[cfg_attr(miri, ignore)]
+    fn test_encode_neon() {
+        if !is_available() {
+            return;
+        }
+        for_each_test_width!(check);
+    }
+
+    #[test]
+    #[cfg_attr(miri, ignore)]
+    fn test_helpers() {
+        if !is_available() {
+            return;
+        }
+        // SAFETY: NEON support was just checked
+        unsafe {
+            let digits: [u8; 64] = core::array::from_fn(|i| (i % 58) as u8);
+            let mut chars = [0; 64];
+            for (digits, chars) in digits.chunks_exact(16).zip(chars.chunks_exact_mut(16)) {
+                let v = to_bitcoin_chars(vld1q_u8(digits.as_ptr()));
+                vst1q_u8(chars.as_mut_ptr(), v);
+            }
+            for (digit, char) in digits.iter().zip(chars) {
+                assert_eq!(Alphabet::BITCOIN.encode[*digit as usize], char);
+            }
+
+            let terms: [u64; 4] = [0, 57, 58 * 58 * 58 * 58 * 58 - 1, 123_456_789];
+            let mut out = [0u64; 4];
+            for (terms, out) in terms.chunks_exact(2).zip(out.chunks_exact_mut(2)) {
+                let v = split_digits(vld1q_u64(terms.as_ptr()));
+                vst1q_u64(out.as_mut_ptr(), v);
+            }
+            for (term, digits) in terms.iter().zip(out) {
+                let digits = digits.to_le_bytes();
+                let value = digits[..5]
+                    .iter()
+                    .fold(0, |acc, &d| acc * 58 + u64::from(d));
+                assert_eq!(value, *term);
+                assert!(digits[..5].iter().all(|&d| d < 58));
+                assert_eq!(digits[5..], [0; 3]);
+            }
+
+            for len in [0, 1, 15, 16, 17, 33, 40] {
+                for zeros in 0..=len {
+                    let mut bytes = vec![1; len];
+                    bytes[..zeros].fill(0);
+                    assert_eq!(leading_zeros(&bytes), zeros);
+                }
+            }
+        }
+    }
+}
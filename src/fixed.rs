@@ -0,0 +1,470 @@
+//! Compile time parameters for the const generic codec in [`crate::encode_fixed`] and
+//! [`crate::decode_fixed`].
+//!
+//! Everything here is derived from the byte count `N` by `const fn`s, so each width only pays
+//! for its tables once, at compile time. The tables are sized for the largest supported width
+//! and only the top-left `BINARY_SZ` x `INTERMEDIATE_SZ` corner is used.
+
+use crate::constants::{MAX_FIXED_BYTE_COUNT, R1_DIV};
+
+pub(crate) const MAX_BINARY_SZ: usize = binary_sz(MAX_FIXED_BYTE_COUNT);
+pub(crate) const MAX_INTERMEDIATE_SZ: usize = intermediate_sz(MAX_FIXED_BYTE_COUNT);
+pub(crate) const MAX_RAW58_SZ: usize = 5 * MAX_INTERMEDIATE_SZ;
+pub(crate) const MAX_ENCODED_LEN: usize = encoded_len(MAX_FIXED_BYTE_COUNT);
+
+/* Scratch big integers used while building the tables, stored as
+little-endian 32-bit limbs.  One spare limb so 256^N itself fits. */
+const BIG_LIMBS: usize = MAX_BINARY_SZ + 1;
+type Big = [u32; BIG_LIMBS];
+
+pub(crate) struct Fixed<const N: usize>;
+
+impl<const N: usize> Fixed<N> {
+    /// Evaluating this rejects unsupported widths at compile time
+    pub(crate) const VALID: () = assert!(
+        N > 0 && N <= MAX_FIXED_BYTE_COUNT,
+        "fixed width base58 supports between 1 and MAX_FIXED_BYTE_COUNT bytes"
+    );
+
+    pub(crate) const BINARY_SZ: usize = binary_sz(N);
+    pub(crate) const INTERMEDIATE_SZ: usize = intermediate_sz(N);
+    pub(crate) const RAW58_SZ: usize = 5 * Self::INTERMEDIATE_SZ;
+    pub(crate) const ENCODED_LEN: usize = encoded_len(N);
+
+    /// Bytes in the most significant limb.  Less than 4 when N isn't a multiple of 4.
+    pub(crate) const HEAD_BYTES: usize = N - 4 * (Self::BINARY_SZ - 1);
+    pub(crate) const HEAD_LIMB_MAX: u64 = (1u64 << (8 * Self::HEAD_BYTES)) - 1;
+
+    pub(crate) const ENC_TABLE: [[u64; MAX_INTERMEDIATE_SZ]; MAX_BINARY_SZ] = enc_table(N);
+    /// First column of ENC_TABLE that isn't all zeros
+    pub(crate) const ENC_START: usize = enc_start(N, &Self::ENC_TABLE);
+    /// Bit j of ENC_REDUCE[i] is set if intermediate[j] must be reduced before adding limb i
+    pub(crate) const ENC_REDUCE: [u64; MAX_BINARY_SZ] = enc_reduce(N, &Self::ENC_TABLE);
+    /// The first limb after i that needs a reduction, or BINARY_SZ
+    pub(crate) const ENC_SEGMENT_END: [usize; MAX_BINARY_SZ] =
+        segment_end(Self::BINARY_SZ, &Self::ENC_REDUCE);
+
+    pub(crate) const DEC_TABLE: [[u64; MAX_BINARY_SZ]; MAX_INTERMEDIATE_SZ] = dec_table(N);
+    /// Bit j of DEC_REDUCE[i] is set if binary[j] must be reduced before adding term i
+    pub(crate) const DEC_REDUCE: [u64; MAX_INTERMEDIATE_SZ] = dec_reduce(N, &Self::DEC_TABLE);
+    /// The first term after i that needs a reduction, or INTERMEDIATE_SZ
+    pub(crate) const DEC_SEGMENT_END: [usize; MAX_INTERMEDIATE_SZ] =
+        segment_end(Self::INTERMEDIATE_SZ, &Self::DEC_REDUCE);
+    pub(crate) const DEC_REDUCE_ANY: bool = Self::DEC_SEGMENT_END[0] < Self::INTERMEDIATE_SZ;
+}
+
+pub(crate) const fn binary_sz(n: usize) -> usize {
+    n.div_ceil(4)
+}
+
+/// Computed by ceil(log_(58^5) (256^n-1))
+pub(crate) const fn intermediate_sz(n: usize) -> usize {
+    digit_count(&max_value(n), R1_DIV as u32)
+}
+
+/// Computed by ceil(log_58 (256^n-1))
+pub(crate) const fn encoded_len(n: usize) -> usize {
+    digit_count(&max_value(n), 58)
+}
+
+/// 256^n - 1
+const fn max_value(n: usize) -> Big {
+    let mut x = [0; BIG_LIMBS];
+    let mut i = 0;
+    while i < n / 4 {
+        x[i] = u32::MAX;
+        i += 1;
+    }
+    if n % 4 != 0 {
+        x[n / 4] = (1 << (8 * (n % 4))) - 1;
+    }
+    x
+}
+
+const fn segment_end<const L: usize>(len: usize, reduce: &[u64; L]) -> [usize; L] {
+    let mut end = [len; L];
+    let mut i = len;
+    while i > 1 {
+        i -= 1;
+        end[i - 1] = if reduce[i] != 0 { i } else { end[i] };
+    }
+    end
+}
+
+const fn is_zero(x: &Big) -> bool {
+    let mut i = 0;
+    while i < BIG_LIMBS {
+        if x[i] != 0 {
+            return false;
+        }
+        i += 1;
+    }
+    true
+}
+
+/// x /= d, returning x % d
+const fn div_rem(x: &mut Big, d: u32) -> u32 {
+    let mut rem: u64 = 0;
+    let mut i = BIG_LIMBS;
+    while i > 0 {
+        i -= 1;
+        let cur = (rem << 32) | x[i] as u64;
+        x[i] = (cur / d as u64) as u32;
+        rem = cur % d as u64;
+    }
+    rem as u32
+}
+
+/// x *= m
+const fn mul(x: &mut Big, m: u32) {
+    let mut carry: u64 = 0;
+    let mut i = 0;
+    while i < BIG_LIMBS {
+        let cur = x[i] as u64 * m as u64 + carry;
+        x[i] = cur as u32;
+        carry = cur >> 32;
+        i += 1;
+    }
+    assert!(carry == 0);
+}
+
+const fn digit_count(x: &Big, base: u32) -> usize {
+    let mut x = *x;
+    let mut cnt = 0;
+    while !is_zero(&x) {
+        div_rem(&mut x, base);
+        cnt += 1;
+    }
+    cnt
+}
+
+/// Contains the unique values less than 58^5 such that:
+/// 2^(32*(BINARY_SZ-1-i)) = sum_j table[i][j]*58^(5*(INTERMEDIATE_SZ-1-j))
+const fn enc_table(n: usize) -> [[u64; MAX_INTERMEDIATE_SZ]; MAX_BINARY_SZ] {
+    let binary_sz = binary_sz(n);
+    let intermediate_sz = intermediate_sz(n);
+    let mut table = [[0; MAX_INTERMEDIATE_SZ]; MAX_BINARY_SZ];
+    let mut i = 0;
+    while i < binary_sz {
+        let mut x = [0; BIG_LIMBS];
+        x[binary_sz - 1 - i] = 1;
+        let mut j = intermediate_sz;
+        while j > 0 {
+            j -= 1;
+            table[i][j] = div_rem(&mut x, R1_DIV as u32) as u64;
+        }
+        assert!(is_zero(&x));
+        i += 1;
+    }
+    table
+}
+
+const fn enc_start(n: usize, table: &[[u64; MAX_INTERMEDIATE_SZ]; MAX_BINARY_SZ]) -> usize {
+    let mut i = 0;
+    while i < binary_sz(n) {
+        if table[i][0] != 0 {
+            return 0;
+        }
+        i += 1;
+    }
+    1
+}
+
+/* Works out where the encoder has to reduce terms of intermediate so
+the sums can't overflow.  We track an upper bound for every term,
+assuming every limb of the input is as large as it can be.  Before
+adding a limb, any term that could overflow is reduced mod 58^5,
+carrying into the next more significant term, which is checked right
+after.  For N==64 this comes out as a single mini-reduction of
+intermediate[16], after the first 9 limbs. */
+const fn enc_reduce(
+    n: usize,
+    table: &[[u64; MAX_INTERMEDIATE_SZ]; MAX_BINARY_SZ],
+) -> [u64; MAX_BINARY_SZ] {
+    let binary_sz = binary_sz(n);
+    let intermediate_sz = intermediate_sz(n);
+    let head_limb_max = (1u128 << (8 * (n - 4 * (binary_sz - 1)))) - 1;
+    let mut reduce = [0; MAX_BINARY_SZ];
+    let mut bound = [0u128; MAX_INTERMEDIATE_SZ];
+    let mut i = 0;
+    while i < binary_sz {
+        let limb_max = if i == 0 {
+            head_limb_max
+        } else {
+            u32::MAX as u128
+        };
+        let mut j = intermediate_sz;
+        while j > 1 {
+            j -= 1;
+            if bound[j] + limb_max * table[i][j] as u128 > u64::MAX as u128 {
+                reduce[i] |= 1 << j;
+                bound[j - 1] += bound[j] / R1_DIV as u128;
+                bound[j] = R1_DIV as u128 - 1;
+            }
+        }
+        let mut j = 0;
+        while j < intermediate_sz {
+            bound[j] += limb_max * table[i][j] as u128;
+            assert!(bound[j] <= u64::MAX as u128);
+            j += 1;
+        }
+        i += 1;
+    }
+    /* The final reduction must not overflow either */
+    let mut j = intermediate_sz;
+    while j > 1 {
+        j -= 1;
+        bound[j - 1] += bound[j] / R1_DIV as u128;
+        assert!(bound[j - 1] <= u64::MAX as u128);
+    }
+    reduce
+}
+
+/// Contains the unique values less than 2^32 such that:
+/// 58^(5*(INTERMEDIATE_SZ-1-i)) = sum_j table[i][j]*2^(32*(BINARY_SZ-1-j))
+const fn dec_table(n: usize) -> [[u64; MAX_BINARY_SZ]; MAX_INTERMEDIATE_SZ] {
+    let binary_sz = binary_sz(n);
+    let intermediate_sz = intermediate_sz(n);
+    let mut table = [[0; MAX_BINARY_SZ]; MAX_INTERMEDIATE_SZ];
+    let mut x = [0; BIG_LIMBS];
+    x[0] = 1;
+    let mut i = intermediate_sz;
+    while i > 0 {
+        i -= 1;
+        let mut j = 0;
+        while j < binary_sz {
+            table[i][j] = x[binary_sz - 1 - j] as u64;
+            j += 1;
+        }
+        mul(&mut x, R1_DIV as u32);
+    }
+    table
+}
+
+/* Same as enc_reduce, but for the accumulation into binary, which is
+reduced mod 2^32.  Every term of intermediate is less than 58^5. */
+const fn dec_reduce(
+    n: usize,
+    table: &[[u64; MAX_BINARY_SZ]; MAX_INTERMEDIATE_SZ],
+) -> [u64; MAX_INTERMEDIATE_SZ] {
+    let binary_sz = binary_sz(n);
+    let intermediate_sz = intermediate_sz(n);
+    let term_max = R1_DIV as u128 - 1;
+    let mut reduce = [0; MAX_INTERMEDIATE_SZ];
+    let mut bound = [0u128; MAX_BINARY_SZ];
+    let mut i = 0;
+    while i < intermediate_sz {
+        let mut j = binary_sz;
+        while j > 1 {
+            j -= 1;
+            if bound[j] + term_max * table[i][j] as u128 > u64::MAX as u128 {
+                reduce[i] |= 1 << j;
+                bound[j - 1] += bound[j] >> 32;
+                bound[j] = u32::MAX as u128;
+            }
+        }
+        let mut j = 0;
+        while j < binary_sz {
+            bound[j] += term_max * table[i][j] as u128;
+            assert!(bound[j] <= u64::MAX as u128);
+            j += 1;
+        }
+        i += 1;
+    }
+    let mut j = binary_sz;
+    while j > 1 {
+        j -= 1;
+        bound[j - 1] += bound[j] >> 32;
+        assert!(bound[j - 1] <= u64::MAX as u128);
+    }
+    reduce
+}
+
+/// Inputs for testing the fixed width codecs against each other: all 0s, all 255s and
+/// `seeds` scrambled patterns, the k-th of which starts with `k % zero_period` zero bytes to
+/// exercise the leading '1' handling.  Under Miri only the first two seeds are used, so the
+/// const generic paths can still be checked there in reasonable time.
+#[cfg(test)]
+pub(crate) fn test_inputs<const N: usize>(seeds: u8, zero_period: usize) -> Vec<[u8; N]> {
+    let seeds = if cfg!(miri) { seeds.min(2) } else { seeds };
+    let mut inputs = vec![[0; N], [255; N]];
+    for seed in 0..seeds {
+        let mut bytes: [u8; N] = core::array::from_fn(|i| (i as u8 ^ seed).wrapping_mul(167));
+        for byte in bytes.iter_mut().take(seed as usize % zero_period) {
+            *byte = 0;
+        }
+        inputs.push(bytes);
+    }
+    inputs
+}
+
+/// Calls `$check::<N>(args)` for every width the fixed width codecs are tested at: partial and
+/// full head limbs, both sides of the reduction thresholds and the largest width.  Miri (also
+/// run on big-endian targets) is too slow for the whole list and only gets a few of them.
+#[cfg(test)]
+macro_rules! for_each_test_width {
+    ($check:ident $(, $arg:expr)*) => {{
+        #[cfg(not(miri))]
+        {
+            $check::<1>($($arg),*);
+            $check::<2>($($arg),*);
+            $check::<3>($($arg),*);
+            $check::<4>($($arg),*);
+            $check::<5>($($arg),*);
+            $check::<20>($($arg),*);
+            $check::<25>($($arg),*);
+            $check::<31>($($arg),*);
+            $check::<32>($($arg),*);
+            $check::<33>($($arg),*);
+            $check::<48>($($arg),*);
+            $check::<64>($($arg),*);
+            $check::<65>($($arg),*);
+            $check::<96>($($arg),*);
+            $check::<127>($($arg),*);
+            $check::<128>($($arg),*);
+        }
+        #[cfg(miri)]
+        {
+            $check::<3>($($arg),*);
+            $check::<32>($($arg),*);
+            $check::<33>($($arg),*);
+            $check::<64>($($arg),*);
+        }
+    }};
+}
+#[cfg(test)]
+pub(crate) use for_each_test_width;
+
+/// Checks a Bitcoin alphabet encoder, e.g. one of the SIMD ones, against
+/// [`encode_fixed_into`](crate::encode_fixed::encode_fixed_into) on every [`test_inputs`] value.
+#[cfg(all(
+    test,
+    any(target_arch = "x86_64", target_arch = "aarch64"),
+    not(feature = "force-scalar")
+))]
+pub(crate) fn check_encoder<const N: usize>(encode: impl Fn(&[u8; N], &mut [u8]) -> usize) {
+    use crate::{alphabet::Alphabet, encode_fixed::encode_fixed_into};
+
+    for bytes in test_inputs::<N>(64, 37) {
+        let mut expected = [0; 256];
+        let expected_len = encode_fixed_into(&bytes, &mut expected, &Alphabet::BITCOIN);
+        let mut out = [0; 256];
+        let len = encode(&bytes, &mut out);
+        assert_eq!(&out[..len], &expected[..expected_len]);
+    }
+}
+
+/// Checks a Bitcoin alphabet decoder, e.g. one of the SIMD ones, against
+/// [`decode_fixed`](crate::decode_fixed::decode_fixed) on `encoded`, errors included.
+#[cfg(all(
+    test,
+    any(target_arch = "x86_64", target_arch = "aarch64"),
+    not(feature = "force-scalar")
+))]
+pub(crate) fn check_decoder<const N: usize>(
+    encoded: &[u8],
+    decode: impl Fn(&[u8]) -> Result<[u8; N], crate::Error>,
+) {
+    use crate::{alphabet::Alphabet, decode_fixed::decode_fixed};
+
+    assert_eq!(
+        decode(encoded),
+        decode_fixed::<N>(encoded, &Alphabet::BITCOIN)
+    );
+}
+
+/// Base58 strings for testing decoders: every length up to the longest 64 byte encoding with
+/// a few leading '1's, the longest 64 and 128 byte strings, and a 32 byte key with every byte
+/// value at the edges of the string and of 16 and 32 byte vectors, alone and repeated to reach
+/// the 64 byte length.
+#[cfg(all(
+    test,
+    any(target_arch = "x86_64", target_arch = "aarch64"),
+    not(feature = "force-scalar")
+))]
+pub(crate) fn test_encoded() -> Vec<Vec<u8>> {
+    let alphabet = crate::alphabet::Alphabet::BITCOIN.as_bytes();
+    let mut strings = Vec::new();
+    for seed in 0..64 {
+        let len = seed * 7 % 90;
+        let mut encoded: Vec<u8> = (0..len)
+            .map(|i| alphabet[(i * 89 + seed * 31) % 58])
+            .collect();
+        for c in encoded.iter_mut().take(seed % 5) {
+            *c = b'1';
+        }
+        strings.push(encoded);
+    }
+    strings.push(vec![b'z'; 88]);
+    strings.push(vec![b'z'; 175]);
+
+    let key = "XkCriyrNwS3G4rzAXtG5B1nnvb5Ka1JtCku93VqeKAr";
+    for valid in [key.to_string(), key.repeat(2)] {
+        for index in [0, 1, 15, 16, 31, 32, 63, 64, valid.len() - 1] {
+            if index >= valid.len() {
+                continue;
+            }
+            for c in 0..=255u8 {
+                let mut encoded = valid.clone().into_bytes();
+                encoded[index] = c;
+                strings.push(encoded);
+            }
+        }
+    }
+    strings.push("XkCriyrNwS3G4rzAXtG5B1nnvb5Ka1JtCku93Vqe€".into());
+    strings
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Fixed;
+    use crate::constants::{
+        BASE58_ENCODED_32_LEN, BASE58_ENCODED_64_LEN, BINARY_SZ_32, BINARY_SZ_64, DEC_TABLE_32,
+        DEC_TABLE_64, ENC_TABLE_32, ENC_TABLE_64, INTERMEDIATE_SZ_32, INTERMEDIATE_SZ_64,
+        RAW58_SZ_32, RAW58_SZ_64,
+    };
+
+    #[test]
+    fn test_sizes_match_constants() {
+        assert_eq!(Fixed::<32>::BINARY_SZ, BINARY_SZ_32);
+        assert_eq!(Fixed::<32>::INTERMEDIATE_SZ, INTERMEDIATE_SZ_32);
+        assert_eq!(Fixed::<32>::RAW58_SZ, RAW58_SZ_32);
+        assert_eq!(Fixed::<32>::ENCODED_LEN as u64, BASE58_ENCODED_32_LEN);
+        assert_eq!(Fixed::<64>::BINARY_SZ, BINARY_SZ_64);
+        assert_eq!(Fixed::<64>::INTERMEDIATE_SZ, INTERMEDIATE_SZ_64);
+        assert_eq!(Fixed::<64>::RAW58_SZ, RAW58_SZ_64);
+        assert_eq!(Fixed::<64>::ENCODED_LEN as u64, BASE58_ENCODED_64_LEN);
+    }
+
+    #[test]
+    fn test_tables_match_constants() {
+        for (row, expected) in Fixed::<32>::ENC_TABLE.iter().zip(ENC_TABLE_32) {
+            assert_eq!(row[0], 0);
+            assert_eq!(row[1..INTERMEDIATE_SZ_32], expected);
+        }
+        for (row, expected) in Fixed::<64>::ENC_TABLE.iter().zip(ENC_TABLE_64) {
+            assert_eq!(row[0], 0);
+            assert_eq!(row[1..INTERMEDIATE_SZ_64], expected.map(u64::from));
+        }
+        for (row, expected) in Fixed::<32>::DEC_TABLE.iter().zip(DEC_TABLE_32) {
+            assert_eq!(row[..BINARY_SZ_32], expected);
+        }
+        for (row, expected) in Fixed::<64>::DEC_TABLE.iter().zip(DEC_TABLE_64) {
+            assert_eq!(row[..BINARY_SZ_64], expected);
+        }
+    }
+
+    #[test]
+    fn test_reduction_schedule() {
+        /* The 32 byte conversions never need to reduce early, and the
+        64 byte encoder needs exactly one mini-reduction of
+        intermediate[16].  intermediate[16] can reach 2^63.92 after 9
+        limbs, so that's when it happens. */
+        assert!(Fixed::<32>::ENC_REDUCE.iter().all(|&r| r == 0));
+        assert!(Fixed::<32>::DEC_REDUCE.iter().all(|&r| r == 0));
+        assert!(Fixed::<64>::DEC_REDUCE.iter().all(|&r| r == 0));
+        for (i, &r) in Fixed::<64>::ENC_REDUCE.iter().enumerate() {
+            assert_eq!(r, if i == 9 { 1 << 16 } else { 0 });
+        }
+    }
+}
@@ -1,21 +1,199 @@
 //! An optimized implementation of [Base58][] encoding/decoding for 32 and 64 byte numbers.
 //! This library is based off of the original C implementation from Jump Crypto's [Firedancer]
 //! repo which can be found [here]. These algorithms are significantly faster than the commonly used
-//! [`bs58`] library for 32 and 64 bytes.
+//! [`bs58`] library for 32 and 64 bytes. The same algorithm is available for any other fixed
+//! width up to [`constants::MAX_FIXED_BYTE_COUNT`] bytes through [`encode_fixed`] and
+//! [`decode_fixed`]. Inputs of any other length can go through [`encode`] and [`decode`], which
+//! use the optimized algorithms when the length allows it and a general one otherwise.
 //!
 //! [Base58]: https://en.wikipedia.org/wiki/Base58
 //! [`bs58`]: https://github.com/Nullus157/bs58-rs
 //! [Firedancer]: https://github.com/firedancer-io/firedancer
 //! [here]: https://github.com/firedancer-io/firedancer/pull/75
+//! [Base58Check]: https://en.bitcoin.it/wiki/Base58Check_encoding
 //!
+//! Everything uses the Bitcoin alphabet, except for the `_with` variants which take an
+//! [`Alphabet`] such as [`Alphabet::RIPPLE`] or [`Alphabet::FLICKR`].
+//!
+//! # Features
+//!
+//! - `std` (default): links the standard library. Implies `alloc`.
+//! - `alloc`: enables the functions returning or appending to an owned `String`.
+//! - `check`: enables [Base58Check] encoding and decoding. Implies `alloc` and
+//!   `checksum-sha256d`.
+//! - `checksum-sha256d`, `checksum-cb58`, `checksum-ss58`, `checksum-ripemd160` and
+//!   `checksum-blake256`: enable the [`checksum`] schemes of Bitcoin, Avalanche, Substrate,
+//!   EOS and Decred for [`encode_checked`] and [`decode_checked`].
+//! - `serde`: enables the [`serde`](crate::serde) adapters, and `Serialize`/`Deserialize`
+//!   for [`Base58Array`].
+//! - `derive`: enables `#[derive(Base58)]`, see [`Base58`].
+//! - `zeroize`: wipes every buffer the codecs work in before returning, and enables the
+//!   `_secret` functions such as [`encode_64_secret`] and [`decode_64_secret`], which return
+//!   [`Zeroizing`] values.
+//! - `rayon`: enables the parallel `_par` variants of the [`bulk`] converters. Implies `std`.
+//! - `cli`: builds the `fd-bs58` binary, which converts between base58 and hex, base64, JSON
+//!   byte arrays or raw bytes. Run `fd-bs58 --help` for usage.
+//! - `force-scalar`: never uses the SIMD code paths, even when the CPU supports them. Handy
+//!   for comparing against the portable code.
+//!
+//! On x86_64, [`encode_32`], [`encode_64`], [`decode_32`] and [`decode_64`] use AVX2 when the
+//! CPU supports it, and on aarch64 they use NEON. Without AVX2, [`decode_32`] and
+//! [`decode_64`] fall back to SSE4.1. Support is detected at runtime with `std` and at
+//! compile time without it.
+//!
+//! With both disabled the crate is `no_std` and still provides the fixed-buffer
+//! encoders and all decoders. [`Error`] implements [`core::error::Error`] either way.
+//!
+//! [`encode_32_ct`], [`encode_64_ct`], [`decode_32_ct`] and [`decode_64_ct`] are constant-time
+//! versions for secret keys, see [`constant_time`].
+//!
+//! [`Base58Str`] and [`Base58String`] are strings whose characters were checked once, so
+//! they can be passed around and decoded without checking them again. [`encode_32_inline`]
+//! and [`encode_64_inline`] return a [`Base58Inline`], a `Copy` string that doesn't allocate.
+//!
+//! [`b58!`] and [`b58_64!`] decode base58 literals at compile time, through the `const fn`
+//! versions [`decode_32_const`] and [`decode_64_const`].
+//!
+
+#![cfg_attr(not(any(feature = "std", test)), no_std)]
+
+#[cfg(feature = "alloc")]
+extern crate alloc;
+
+#[cfg(feature = "alloc")]
+use alloc::{string::String, vec::Vec};
+
+pub use alphabet::{Alphabet, AlphabetError};
+pub use array::{Base58Array, Bs58Bytes32, Bs58Bytes64};
+use constants::{BASE58_ENCODED_32_LEN, BASE58_ENCODED_64_LEN, BYTE_COUNT_32, BYTE_COUNT_64};
+#[cfg(feature = "alloc")]
+pub use string::Base58String;
+pub use string::{Base58Inline, Base58Str};
+#[cfg(feature = "zeroize")]
+pub use zeroize::Zeroizing;
+
+/// Derives `Display`, `FromStr` and `Debug` as base58 for a struct with a single `[u8; 32]`
+/// or `[u8; 64]` field, such as a public key or signature newtype. They go through the
+/// optimized codec for that width, and any other width is a compile error.
+///
+/// Options go in a `#[base58(...)]` attribute:
+/// - `debug = "truncated"`: `Debug` only shows the first and last 4 characters.
+/// - `alphabet = PATH`: uses the given [`Alphabet`] constant instead of the Bitcoin one.
+/// - `serde`: also implements `Serialize` and `Deserialize` like the [`serde`](crate::serde)
+///   adapters, which needs the `serde` feature.
+///
+/// # Examples
+///
+/// ## Basic example
+///
+/// ```rust
+/// use fd_bs58::Base58;
+///
+/// #[derive(Clone, Copy, PartialEq, Base58)]
+/// struct ProgramId([u8; 32]);
+///
+/// #[derive(Base58)]
+/// #[base58(debug = "truncated")]
+/// struct Signature {
+///     bytes: [u8; 64],
+/// }
+///
+/// let id: ProgramId = "TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA".parse()?;
+/// assert_eq!("TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA", id.to_string());
+/// assert_eq!("ProgramId(TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA)", format!("{id:?}"));
+///
+/// let signature = Signature { bytes: [0; 64] };
+/// assert_eq!("Signature(1111…1111)", format!("{signature:?}"));
+/// # Ok::<(), fd_bs58::Error>(())
+/// ```
+///
+/// ## Errors
+///
+/// ```rust,compile_fail
+/// #[derive(fd_bs58::Base58)]
+/// struct Address([u8; 20]);
+/// ```
+#[cfg(feature = "derive")]
+pub use fd_bs58_derive::Base58;
 
-use constants::{BYTE_COUNT_32, BYTE_COUNT_64};
+/* The derived code refers to ::fd_bs58, which needs this for the tests in this crate */
+#[cfg(all(test, feature = "derive"))]
+extern crate self as fd_bs58;
 
+pub mod alphabet;
+pub mod array;
+#[cfg(feature = "checksum-blake256")]
+mod blake256;
+#[cfg(feature = "std")]
+pub mod bulk;
+#[cfg(feature = "check")]
+pub mod check;
+pub mod checksum;
+pub mod constant_time;
 pub mod constants;
+#[cfg(feature = "alloc")]
+pub mod decode;
 pub mod decode_32;
 pub mod decode_64;
+#[cfg(all(target_arch = "x86_64", not(feature = "force-scalar")))]
+mod decode_avx2;
+#[cfg(feature = "alloc")]
+pub mod decode_batch;
+pub mod decode_fixed;
+#[cfg(all(target_arch = "aarch64", not(feature = "force-scalar")))]
+mod decode_neon;
+#[cfg(all(target_arch = "x86_64", not(feature = "force-scalar")))]
+mod decode_sse41;
+#[cfg(feature = "derive")]
+#[doc(hidden)]
+pub mod derive;
+#[cfg(feature = "alloc")]
+pub mod encode;
 pub mod encode_32;
 pub mod encode_64;
+#[cfg(all(target_arch = "x86_64", not(feature = "force-scalar")))]
+mod encode_avx2;
+pub mod encode_batch;
+pub mod encode_fixed;
+#[cfg(all(target_arch = "aarch64", not(feature = "force-scalar")))]
+mod encode_neon;
+mod fixed;
+mod scratch;
+#[cfg(feature = "serde")]
+pub mod serde;
+pub mod string;
+
+/// Encodes bytes of any length to base58. 32 and 64 byte inputs use the optimized
+/// [`encode_32`] and [`encode_64`] algorithms, anything else a general one giving the same output.
+///
+/// # Examples
+///
+/// ## Basic example
+///
+/// ```rust
+/// assert_eq!("2NEpo7TZRRrLZSi2U", fd_bs58::encode(b"Hello World!"));
+/// assert_eq!("11", fd_bs58::encode([0, 0]));
+/// ```
+#[cfg(feature = "alloc")]
+pub fn encode<I: AsRef<[u8]>>(input: I) -> String {
+    encode::encode(input.as_ref(), &Alphabet::BITCOIN)
+}
+
+/// Encodes bytes of any length like [`encode`], using the given alphabet.
+///
+/// # Examples
+///
+/// ## Basic example
+///
+/// ```rust
+/// use fd_bs58::Alphabet;
+///
+/// assert_eq!("rrpVC7X", fd_bs58::encode_with([0, 0, 1, 2, 3, 4], &Alphabet::RIPPLE));
+/// ```
+#[cfg(feature = "alloc")]
+pub fn encode_with<I: AsRef<[u8]>>(input: I, alphabet: &Alphabet) -> String {
+    encode::encode(input.as_ref(), alphabet)
+}
 
 /// Encodes the given 32 bytes using an optimized base58 encoding algorithm.
 ///
@@ -25,14 +203,30 @@ pub mod encode_64;
 ///
 /// ```rust
 /// assert_eq!(
-///     [7, 224, 70, 147, 60, 112, 144, 250, 46, 62, 133, 57, 252, 149, 220, 143, 237, 77, 21, 208, 191, 61, 58, 206, 152, 136, 129, 103, 129, 48, 141, 139],
-///     fd_bs58::decode_32("XkCriyrNwS3G4rzAXtG5B1nnvb5Ka1JtCku93VqeKAr")?);
-/// # Ok::<(), fd_bs58::Error>(())
+///     "XkCriyrNwS3G4rzAXtG5B1nnvb5Ka1JtCku93VqeKAr",
+///     fd_bs58::encode_32(&[7, 224, 70, 147, 60, 112, 144, 250, 46, 62, 133, 57, 252, 149, 220, 143, 237, 77, 21, 208, 191, 61, 58, 206, 152, 136, 129, 103, 129, 48, 141, 139]));
 /// ```
-pub fn encode_32<I: AsRef<[u8]>>(input: I) -> String {
+#[cfg(feature = "alloc")]
+pub fn encode_32(input: &[u8; BYTE_COUNT_32]) -> String {
     encode_32::encode_32(input)
 }
 
+/// Encodes the given 32 bytes like [`encode_32`], using the given alphabet.
+///
+/// # Examples
+///
+/// ## Basic example
+///
+/// ```rust
+/// use fd_bs58::Alphabet;
+///
+/// assert_eq!("rrrrrrrrrrrrrrrrrrrrrrrrrrrrrrrr", fd_bs58::encode_32_with(&[0; 32], &Alphabet::RIPPLE));
+/// ```
+#[cfg(feature = "alloc")]
+pub fn encode_32_with(input: &[u8; BYTE_COUNT_32], alphabet: &Alphabet) -> String {
+    encode_32::encode_32_with(input, alphabet)
+}
+
 /// Encodes the given 64 bytes using an optimized base58 encoding algorithm.
 ///
 /// # Examples
@@ -41,12 +235,544 @@ pub fn encode_32<I: AsRef<[u8]>>(input: I) -> String {
 ///
 /// ```rust
 /// assert_eq!(
-///     [7, 224, 70, 147, 60, 112, 144, 250, 46, 62, 133, 57, 252, 149, 220, 143, 237, 77, 21, 208, 191, 61, 58, 206, 152, 136, 129, 103, 129, 48, 141, 139],
-///     fd_bs58::decode_32("XkCriyrNwS3G4rzAXtG5B1nnvb5Ka1JtCku93VqeKAr")?);
+///     "11cgTH4D5e8S3snD444WbbGrkepjTvWMj2jkmCGJtgn3H7qrPb1BnwapxpbGdRtHQh9t9Wbn9t6ZDGHzWpL4df",
+///     fd_bs58::encode_64(&[0, 0, 10, 85, 198, 191, 71, 18, 5, 54, 6, 255, 181, 32, 227, 150, 208, 3, 157, 135, 222, 67, 50, 23, 237, 51, 240, 123, 34, 148, 111, 84, 98, 162, 236, 133, 31, 93, 185, 142, 108, 41, 191, 1, 138, 6, 192, 0, 46, 93, 25, 65, 243, 223, 225, 225, 85, 55, 82, 251, 109, 132, 165, 2]));
+/// ```
+#[cfg(feature = "alloc")]
+pub fn encode_64(input: &[u8; BYTE_COUNT_64]) -> String {
+    encode_64::encode_64(input)
+}
+
+/// Encodes the given 64 bytes like [`encode_64`], using the given alphabet.
+///
+/// # Examples
+///
+/// ## Basic example
+///
+/// ```rust
+/// use fd_bs58::Alphabet;
+///
+/// assert_eq!("rrrrrrrrrrrrrrrrrrrrrrrrrrrrrrrrrrrrrrrrrrrrrrrrrrrrrrrrrrrrrrrr", fd_bs58::encode_64_with(&[0; 64], &Alphabet::RIPPLE));
+/// ```
+#[cfg(feature = "alloc")]
+pub fn encode_64_with(input: &[u8; BYTE_COUNT_64], alphabet: &Alphabet) -> String {
+    encode_64::encode_64_with(input, alphabet)
+}
+
+/// Encodes the given bytes using an optimized base58 encoding algorithm.
+/// This function will return an error instead of panicking if the input is not exactly 32 bytes.
+///
+/// # Examples
+///
+/// ## Basic example
+///
+/// ```rust
+/// let bytes: Vec<u8> = vec![7, 224, 70, 147, 60, 112, 144, 250, 46, 62, 133, 57, 252, 149, 220, 143, 237, 77, 21, 208, 191, 61, 58, 206, 152, 136, 129, 103, 129, 48, 141, 139];
+/// assert_eq!(
+///     "XkCriyrNwS3G4rzAXtG5B1nnvb5Ka1JtCku93VqeKAr",
+///     fd_bs58::try_encode_32(&bytes)?);
+/// # Ok::<(), fd_bs58::Error>(())
+/// ```
+///
+/// ## Errors
+///
+/// ### Invalid Input Length
+///
+/// ```rust
+/// assert_eq!(
+///     fd_bs58::Error::InvalidInputLength { expected: 32, actual: 31 },
+///     fd_bs58::try_encode_32([0; 31]).unwrap_err());
+/// ```
+#[cfg(feature = "alloc")]
+pub fn try_encode_32<I: AsRef<[u8]>>(input: I) -> Result<String, Error> {
+    encode_32::try_encode_32(input)
+}
+
+/// Encodes the given bytes using an optimized base58 encoding algorithm.
+/// This function will return an error instead of panicking if the input is not exactly 64 bytes.
+///
+/// # Examples
+///
+/// ## Basic example
+///
+/// ```rust
+/// let bytes: Vec<u8> = vec![0, 0, 10, 85, 198, 191, 71, 18, 5, 54, 6, 255, 181, 32, 227, 150, 208, 3, 157, 135, 222, 67, 50, 23, 237, 51, 240, 123, 34, 148, 111, 84, 98, 162, 236, 133, 31, 93, 185, 142, 108, 41, 191, 1, 138, 6, 192, 0, 46, 93, 25, 65, 243, 223, 225, 225, 85, 55, 82, 251, 109, 132, 165, 2];
+/// assert_eq!(
+///     "11cgTH4D5e8S3snD444WbbGrkepjTvWMj2jkmCGJtgn3H7qrPb1BnwapxpbGdRtHQh9t9Wbn9t6ZDGHzWpL4df",
+///     fd_bs58::try_encode_64(&bytes)?);
+/// # Ok::<(), fd_bs58::Error>(())
+/// ```
+///
+/// ## Errors
+///
+/// ### Invalid Input Length
+///
+/// ```rust
+/// assert_eq!(
+///     fd_bs58::Error::InvalidInputLength { expected: 64, actual: 65 },
+///     fd_bs58::try_encode_64([0; 65]).unwrap_err());
+/// ```
+#[cfg(feature = "alloc")]
+pub fn try_encode_64<I: AsRef<[u8]>>(input: I) -> Result<String, Error> {
+    encode_64::try_encode_64(input)
+}
+
+/// Encodes the given 32 bytes into a caller-provided buffer without allocating.
+/// Returns the number of ASCII characters written to the start of `output`.
+///
+/// # Examples
+///
+/// ## Basic example
+///
+/// ```rust
+/// let bytes = [7, 224, 70, 147, 60, 112, 144, 250, 46, 62, 133, 57, 252, 149, 220, 143, 237, 77, 21, 208, 191, 61, 58, 206, 152, 136, 129, 103, 129, 48, 141, 139];
+/// let mut output = [0; 44];
+/// let len = fd_bs58::encode_32_into(&bytes, &mut output);
+/// assert_eq!(b"XkCriyrNwS3G4rzAXtG5B1nnvb5Ka1JtCku93VqeKAr", &output[..len]);
+/// ```
+pub fn encode_32_into(
+    input: &[u8; BYTE_COUNT_32],
+    output: &mut [u8; BASE58_ENCODED_32_LEN as usize],
+) -> usize {
+    encode_32::encode_32_into(input, output)
+}
+
+/// Encodes the given 64 bytes into a caller-provided buffer without allocating.
+/// Returns the number of ASCII characters written to the start of `output`.
+///
+/// # Examples
+///
+/// ## Basic example
+///
+/// ```rust
+/// let bytes = [0, 0, 10, 85, 198, 191, 71, 18, 5, 54, 6, 255, 181, 32, 227, 150, 208, 3, 157, 135, 222, 67, 50, 23, 237, 51, 240, 123, 34, 148, 111, 84, 98, 162, 236, 133, 31, 93, 185, 142, 108, 41, 191, 1, 138, 6, 192, 0, 46, 93, 25, 65, 243, 223, 225, 225, 85, 55, 82, 251, 109, 132, 165, 2];
+/// let mut output = [0; 88];
+/// let len = fd_bs58::encode_64_into(&bytes, &mut output);
+/// assert_eq!(b"11cgTH4D5e8S3snD444WbbGrkepjTvWMj2jkmCGJtgn3H7qrPb1BnwapxpbGdRtHQh9t9Wbn9t6ZDGHzWpL4df", &output[..len]);
+/// ```
+pub fn encode_64_into(
+    input: &[u8; BYTE_COUNT_64],
+    output: &mut [u8; BASE58_ENCODED_64_LEN as usize],
+) -> usize {
+    encode_64::encode_64_into(input, output)
+}
+
+/// Encodes many 32 byte inputs, writing `inputs[i]` to `outputs[i]` and its length to
+/// `lens[i]`. This is a convenience wrapper that calls [`encode_32_into`] on each input, it
+/// isn't any faster than doing so yourself.
+///
+/// # Panics
+///
+/// Panics if `outputs` or `lens` is shorter than `inputs`.
+///
+/// # Examples
+///
+/// ## Basic example
+///
+/// ```rust
+/// let keys = [[0; 32], [7, 224, 70, 147, 60, 112, 144, 250, 46, 62, 133, 57, 252, 149, 220, 143, 237, 77, 21, 208, 191, 61, 58, 206, 152, 136, 129, 103, 129, 48, 141, 139]];
+/// let mut outputs = [[0; 44]; 2];
+/// let mut lens = [0; 2];
+/// fd_bs58::encode_32_batch(&keys, &mut outputs, &mut lens);
+/// assert_eq!(b"11111111111111111111111111111111", &outputs[0][..lens[0] as usize]);
+/// assert_eq!(b"XkCriyrNwS3G4rzAXtG5B1nnvb5Ka1JtCku93VqeKAr", &outputs[1][..lens[1] as usize]);
+/// ```
+pub fn encode_32_batch(
+    inputs: &[[u8; BYTE_COUNT_32]],
+    outputs: &mut [[u8; BASE58_ENCODED_32_LEN as usize]],
+    lens: &mut [u8],
+) {
+    encode_32::encode_32_batch(inputs, outputs, lens)
+}
+
+/// Encodes the given 32 bytes and appends the result to `output`.
+/// No allocation happens if `output` already has enough spare capacity.
+///
+/// # Examples
+///
+/// ## Basic example
+///
+/// ```rust
+/// let bytes = [7, 224, 70, 147, 60, 112, 144, 250, 46, 62, 133, 57, 252, 149, 220, 143, 237, 77, 21, 208, 191, 61, 58, 206, 152, 136, 129, 103, 129, 48, 141, 139];
+/// let mut output = String::from("key: ");
+/// fd_bs58::encode_32_into_string(&bytes, &mut output);
+/// assert_eq!("key: XkCriyrNwS3G4rzAXtG5B1nnvb5Ka1JtCku93VqeKAr", output);
+/// ```
+#[cfg(feature = "alloc")]
+pub fn encode_32_into_string(input: &[u8; BYTE_COUNT_32], output: &mut String) {
+    encode_32::encode_32_into_string(input, output)
+}
+
+/// Encodes many 64 byte inputs, writing `inputs[i]` to `outputs[i]` and its length to
+/// `lens[i]`. This is a convenience wrapper that calls [`encode_64_into`] on each input, it
+/// isn't any faster than doing so yourself.
+///
+/// # Panics
+///
+/// Panics if `outputs` or `lens` is shorter than `inputs`.
+///
+/// # Examples
+///
+/// ## Basic example
+///
+/// ```rust
+/// let signatures = [[0; 64], [255; 64]];
+/// let mut outputs = [[0; 88]; 2];
+/// let mut lens = [0; 2];
+/// fd_bs58::encode_64_batch(&signatures, &mut outputs, &mut lens);
+/// assert_eq!(b"1111111111111111111111111111111111111111111111111111111111111111", &outputs[0][..lens[0] as usize]);
+/// assert_eq!(b"67rpwLCuS5DGA8KGZXKsVQ7dnPb9goRLoKfgGbLfQg9WoLUgNY77E2jT11fem3coV9nAkguBACzrU1iyZM4B8roQ", &outputs[1][..lens[1] as usize]);
+/// ```
+pub fn encode_64_batch(
+    inputs: &[[u8; BYTE_COUNT_64]],
+    outputs: &mut [[u8; BASE58_ENCODED_64_LEN as usize]],
+    lens: &mut [u8],
+) {
+    encode_64::encode_64_batch(inputs, outputs, lens)
+}
+
+/// Encodes the given 64 bytes and appends the result to `output`.
+/// No allocation happens if `output` already has enough spare capacity.
+///
+/// # Examples
+///
+/// ## Basic example
+///
+/// ```rust
+/// let bytes = [0, 0, 10, 85, 198, 191, 71, 18, 5, 54, 6, 255, 181, 32, 227, 150, 208, 3, 157, 135, 222, 67, 50, 23, 237, 51, 240, 123, 34, 148, 111, 84, 98, 162, 236, 133, 31, 93, 185, 142, 108, 41, 191, 1, 138, 6, 192, 0, 46, 93, 25, 65, 243, 223, 225, 225, 85, 55, 82, 251, 109, 132, 165, 2];
+/// let mut output = String::from("sig: ");
+/// fd_bs58::encode_64_into_string(&bytes, &mut output);
+/// assert_eq!("sig: 11cgTH4D5e8S3snD444WbbGrkepjTvWMj2jkmCGJtgn3H7qrPb1BnwapxpbGdRtHQh9t9Wbn9t6ZDGHzWpL4df", output);
+/// ```
+#[cfg(feature = "alloc")]
+pub fn encode_64_into_string(input: &[u8; BYTE_COUNT_64], output: &mut String) {
+    encode_64::encode_64_into_string(input, output)
+}
+
+/// Encodes the given 32 bytes directly into any [`core::fmt::Write`] sink,
+/// such as a [`core::fmt::Formatter`], without allocating.
+///
+/// # Examples
+///
+/// ## Basic example
+///
+/// ```rust
+/// struct Pubkey([u8; 32]);
+///
+/// impl std::fmt::Display for Pubkey {
+///     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+///         fd_bs58::encode_32_write(&self.0, f)
+///     }
+/// }
+///
+/// let key = Pubkey([7, 224, 70, 147, 60, 112, 144, 250, 46, 62, 133, 57, 252, 149, 220, 143, 237, 77, 21, 208, 191, 61, 58, 206, 152, 136, 129, 103, 129, 48, 141, 139]);
+/// assert_eq!("XkCriyrNwS3G4rzAXtG5B1nnvb5Ka1JtCku93VqeKAr", key.to_string());
+/// ```
+pub fn encode_32_write<W: core::fmt::Write>(
+    input: &[u8; BYTE_COUNT_32],
+    output: &mut W,
+) -> core::fmt::Result {
+    encode_32::encode_32_write(input, output)
+}
+
+/// Encodes the given 64 bytes directly into any [`core::fmt::Write`] sink,
+/// such as a [`core::fmt::Formatter`], without allocating.
+///
+/// # Examples
+///
+/// ## Basic example
+///
+/// ```rust
+/// use std::fmt::Write;
+///
+/// let bytes = [0, 0, 10, 85, 198, 191, 71, 18, 5, 54, 6, 255, 181, 32, 227, 150, 208, 3, 157, 135, 222, 67, 50, 23, 237, 51, 240, 123, 34, 148, 111, 84, 98, 162, 236, 133, 31, 93, 185, 142, 108, 41, 191, 1, 138, 6, 192, 0, 46, 93, 25, 65, 243, 223, 225, 225, 85, 55, 82, 251, 109, 132, 165, 2];
+/// let mut output = String::new();
+/// write!(output, "sig=")?;
+/// fd_bs58::encode_64_write(&bytes, &mut output)?;
+/// assert_eq!("sig=11cgTH4D5e8S3snD444WbbGrkepjTvWMj2jkmCGJtgn3H7qrPb1BnwapxpbGdRtHQh9t9Wbn9t6ZDGHzWpL4df", output);
+/// # Ok::<(), std::fmt::Error>(())
+/// ```
+pub fn encode_64_write<W: core::fmt::Write>(
+    input: &[u8; BYTE_COUNT_64],
+    output: &mut W,
+) -> core::fmt::Result {
+    encode_64::encode_64_write(input, output)
+}
+
+/// Encodes the given 32 bytes into a [`Base58Inline`], which keeps the string inline
+/// instead of allocating and is `Copy`.
+///
+/// # Examples
+///
+/// ## Basic example
+///
+/// ```rust
+/// #[derive(Clone, Copy)]
+/// struct Account {
+///     address: fd_bs58::Base58Inline<44>,
+///     lamports: u64,
+/// }
+///
+/// let account = Account {
+///     address: fd_bs58::encode_32_inline(&[7, 224, 70, 147, 60, 112, 144, 250, 46, 62, 133, 57, 252, 149, 220, 143, 237, 77, 21, 208, 191, 61, 58, 206, 152, 136, 129, 103, 129, 48, 141, 139]),
+///     lamports: 1,
+/// };
+/// assert_eq!("XkCriyrNwS3G4rzAXtG5B1nnvb5Ka1JtCku93VqeKAr", &*account.address);
+/// ```
+pub fn encode_32_inline(
+    input: &[u8; BYTE_COUNT_32],
+) -> Base58Inline<{ BASE58_ENCODED_32_LEN as usize }> {
+    encode_32::encode_32_inline(input)
+}
+
+/// Encodes the given 64 bytes into a [`Base58Inline`], which keeps the string inline
+/// instead of allocating and is `Copy`.
+///
+/// # Examples
+///
+/// ## Basic example
+///
+/// ```rust
+/// let signature = fd_bs58::encode_64_inline(&[0; 64]);
+/// assert_eq!("1".repeat(64), signature.to_string());
+/// assert!(signature.starts_with("111"));
+/// ```
+pub fn encode_64_inline(
+    input: &[u8; BYTE_COUNT_64],
+) -> Base58Inline<{ BASE58_ENCODED_64_LEN as usize }> {
+    encode_64::encode_64_inline(input)
+}
+
+/// Encodes the given `N` bytes using the same optimized algorithm as [`encode_32`] and
+/// [`encode_64`], for any `N` from 1 to [`constants::MAX_FIXED_BYTE_COUNT`].
+/// Using any other `N` is a compile time error.
+///
+/// # Examples
+///
+/// ## Basic example
+///
+/// ```rust
+/// // a Bitcoin hash160
+/// let hash = [0x75, 0x1e, 0x76, 0xe8, 0x19, 0x91, 0x96, 0xd4, 0x54, 0x94, 0x1c, 0x45, 0xd1, 0xb3, 0xa3, 0x23, 0xf1, 0x43, 0x3b, 0xd6];
+/// assert_eq!("2ddvLKZUnFosBYkLrzayChzQUNzq", fd_bs58::encode_fixed(&hash));
+/// ```
+#[cfg(feature = "alloc")]
+pub fn encode_fixed<const N: usize>(input: &[u8; N]) -> String {
+    encode_fixed::encode_fixed(input, &Alphabet::BITCOIN)
+}
+
+/// Encodes the given `N` bytes into a caller-provided buffer without allocating.
+/// Returns the number of ASCII characters written to the start of `output`.
+///
+/// # Panics
+///
+/// Panics if `output` is shorter than [`max_encoded_len(N)`](max_encoded_len).
+///
+/// # Examples
+///
+/// ## Basic example
+///
+/// ```rust
+/// let hash = [0x75, 0x1e, 0x76, 0xe8, 0x19, 0x91, 0x96, 0xd4, 0x54, 0x94, 0x1c, 0x45, 0xd1, 0xb3, 0xa3, 0x23, 0xf1, 0x43, 0x3b, 0xd6];
+/// let mut output = [0; fd_bs58::max_encoded_len(20)];
+/// let len = fd_bs58::encode_fixed_into(&hash, &mut output);
+/// assert_eq!(b"2ddvLKZUnFosBYkLrzayChzQUNzq", &output[..len]);
+/// ```
+pub fn encode_fixed_into<const N: usize>(input: &[u8; N], output: &mut [u8]) -> usize {
+    encode_fixed::encode_fixed_into_fast(input, output, &Alphabet::BITCOIN)
+}
+
+/// Encodes the given 32 bytes like [`encode_32`], but in constant time: neither the time it
+/// takes nor the memory it touches depends on the bytes, which makes it suitable for secret keys.
+/// The length of the result still depends on the value, as it does for any base58 encoding.
+/// This is slower than [`encode_32`] and never uses the SIMD code paths.
+///
+/// # Examples
+///
+/// ## Basic example
+///
+/// ```rust
+/// let bytes = [7, 224, 70, 147, 60, 112, 144, 250, 46, 62, 133, 57, 252, 149, 220, 143, 237, 77, 21, 208, 191, 61, 58, 206, 152, 136, 129, 103, 129, 48, 141, 139];
+/// assert_eq!(fd_bs58::encode_32(&bytes), fd_bs58::encode_32_ct(&bytes));
+/// ```
+#[cfg(feature = "alloc")]
+pub fn encode_32_ct(input: &[u8; BYTE_COUNT_32]) -> String {
+    constant_time::encode_fixed_ct(input, &Alphabet::BITCOIN)
+}
+
+/// Encodes the given 64 bytes like [`encode_64`], but in constant time. See [`encode_32_ct`].
+///
+/// # Examples
+///
+/// ## Basic example
+///
+/// ```rust
+/// let keypair = [0, 0, 10, 85, 198, 191, 71, 18, 5, 54, 6, 255, 181, 32, 227, 150, 208, 3, 157, 135, 222, 67, 50, 23, 237, 51, 240, 123, 34, 148, 111, 84, 98, 162, 236, 133, 31, 93, 185, 142, 108, 41, 191, 1, 138, 6, 192, 0, 46, 93, 25, 65, 243, 223, 225, 225, 85, 55, 82, 251, 109, 132, 165, 2];
+/// assert_eq!(
+///     "11cgTH4D5e8S3snD444WbbGrkepjTvWMj2jkmCGJtgn3H7qrPb1BnwapxpbGdRtHQh9t9Wbn9t6ZDGHzWpL4df",
+///     fd_bs58::encode_64_ct(&keypair));
+/// ```
+#[cfg(feature = "alloc")]
+pub fn encode_64_ct(input: &[u8; BYTE_COUNT_64]) -> String {
+    constant_time::encode_fixed_ct(input, &Alphabet::BITCOIN)
+}
+
+/// Encodes the given 32 bytes in constant time into a caller-provided buffer without
+/// allocating, like [`encode_32_into`]. Returns the number of ASCII characters written to the
+/// start of `output`. See [`encode_32_ct`].
+///
+/// # Examples
+///
+/// ## Basic example
+///
+/// ```rust
+/// let bytes = [7, 224, 70, 147, 60, 112, 144, 250, 46, 62, 133, 57, 252, 149, 220, 143, 237, 77, 21, 208, 191, 61, 58, 206, 152, 136, 129, 103, 129, 48, 141, 139];
+/// let mut output = [0; 44];
+/// let len = fd_bs58::encode_32_ct_into(&bytes, &mut output);
+/// assert_eq!(b"XkCriyrNwS3G4rzAXtG5B1nnvb5Ka1JtCku93VqeKAr", &output[..len]);
+/// ```
+pub fn encode_32_ct_into(
+    input: &[u8; BYTE_COUNT_32],
+    output: &mut [u8; BASE58_ENCODED_32_LEN as usize],
+) -> usize {
+    constant_time::encode_fixed_ct_into(input, output, &Alphabet::BITCOIN)
+}
+
+/// Encodes the given 64 bytes in constant time into a caller-provided buffer without
+/// allocating, like [`encode_64_into`]. Returns the number of ASCII characters written to the
+/// start of `output`. See [`encode_32_ct`].
+///
+/// # Examples
+///
+/// ## Basic example
+///
+/// ```rust
+/// let keypair = [0, 0, 10, 85, 198, 191, 71, 18, 5, 54, 6, 255, 181, 32, 227, 150, 208, 3, 157, 135, 222, 67, 50, 23, 237, 51, 240, 123, 34, 148, 111, 84, 98, 162, 236, 133, 31, 93, 185, 142, 108, 41, 191, 1, 138, 6, 192, 0, 46, 93, 25, 65, 243, 223, 225, 225, 85, 55, 82, 251, 109, 132, 165, 2];
+/// let mut output = [0; 88];
+/// let len = fd_bs58::encode_64_ct_into(&keypair, &mut output);
+/// assert_eq!(b"11cgTH4D5e8S3snD444WbbGrkepjTvWMj2jkmCGJtgn3H7qrPb1BnwapxpbGdRtHQh9t9Wbn9t6ZDGHzWpL4df", &output[..len]);
+/// ```
+pub fn encode_64_ct_into(
+    input: &[u8; BYTE_COUNT_64],
+    output: &mut [u8; BASE58_ENCODED_64_LEN as usize],
+) -> usize {
+    constant_time::encode_fixed_ct_into(input, output, &Alphabet::BITCOIN)
+}
+
+/// Encodes the given 32 bytes like [`encode_32_ct`], returning a string that is wiped when
+/// dropped. Along with the `zeroize` feature wiping every buffer the encoder works in, this
+/// leaves no copy of a secret key behind.
+///
+/// # Examples
+///
+/// ## Basic example
+///
+/// ```rust
+/// let secret = [7, 224, 70, 147, 60, 112, 144, 250, 46, 62, 133, 57, 252, 149, 220, 143, 237, 77, 21, 208, 191, 61, 58, 206, 152, 136, 129, 103, 129, 48, 141, 139];
+/// let encoded = fd_bs58::encode_32_secret(&secret);
+/// assert_eq!("XkCriyrNwS3G4rzAXtG5B1nnvb5Ka1JtCku93VqeKAr", encoded.as_str());
+/// ```
+#[cfg(all(feature = "zeroize", feature = "alloc"))]
+pub fn encode_32_secret(input: &[u8; BYTE_COUNT_32]) -> Zeroizing<String> {
+    Zeroizing::new(constant_time::encode_fixed_ct(input, &Alphabet::BITCOIN))
+}
+
+/// Encodes the given 64 bytes, such as an Ed25519 keypair, like [`encode_64_ct`], returning a
+/// string that is wiped when dropped. See [`encode_32_secret`].
+///
+/// # Examples
+///
+/// ## Basic example
+///
+/// ```rust
+/// let keypair = [0, 0, 10, 85, 198, 191, 71, 18, 5, 54, 6, 255, 181, 32, 227, 150, 208, 3, 157, 135, 222, 67, 50, 23, 237, 51, 240, 123, 34, 148, 111, 84, 98, 162, 236, 133, 31, 93, 185, 142, 108, 41, 191, 1, 138, 6, 192, 0, 46, 93, 25, 65, 243, 223, 225, 225, 85, 55, 82, 251, 109, 132, 165, 2];
+/// let encoded = fd_bs58::encode_64_secret(&keypair);
+/// assert_eq!("11cgTH4D5e8S3snD444WbbGrkepjTvWMj2jkmCGJtgn3H7qrPb1BnwapxpbGdRtHQh9t9Wbn9t6ZDGHzWpL4df", encoded.as_str());
+/// ```
+#[cfg(all(feature = "zeroize", feature = "alloc"))]
+pub fn encode_64_secret(input: &[u8; BYTE_COUNT_64]) -> Zeroizing<String> {
+    Zeroizing::new(constant_time::encode_fixed_ct(input, &Alphabet::BITCOIN))
+}
+
+/// Encodes the given 32 bytes like [`encode_32_into`], as a `const fn`. Returns the output
+/// buffer and the length of the encoding at its start. It is much slower than
+/// [`encode_32_into`], so it is meant for constants and tests.
+///
+/// # Examples
+///
+/// ## Basic example
+///
+/// ```rust
+/// const SYSTEM_PROGRAM: [u8; 32] = [0; 32];
+/// const ENCODED: ([u8; 44], usize) = fd_bs58::encode_32_const(&SYSTEM_PROGRAM);
+/// assert_eq!(b"11111111111111111111111111111111", &ENCODED.0[..ENCODED.1]);
+/// ```
+pub const fn encode_32_const(
+    input: &[u8; BYTE_COUNT_32],
+) -> ([u8; BASE58_ENCODED_32_LEN as usize], usize) {
+    encode_32::encode_32_const(input)
+}
+
+/// Returns the longest possible base58 encoding of `byte_count` bytes,
+/// computed as ceil(log_58(256^byte_count - 1)).
+///
+/// # Examples
+///
+/// ## Basic example
+///
+/// ```rust
+/// assert_eq!(44, fd_bs58::max_encoded_len(32));
+/// assert_eq!(88, fd_bs58::max_encoded_len(64));
+/// ```
+pub const fn max_encoded_len(byte_count: usize) -> usize {
+    fixed::encoded_len(byte_count)
+}
+
+/// Decodes a base58 string of any length. Strings that could hold 32 or 64 bytes are tried with
+/// the optimized [`decode_32`] and [`decode_64`] algorithms first, anything else goes through a
+/// general one giving the same output.
+/// This function will return an error if the string is not base58 encoded.
+///
+/// # Examples
+///
+/// ## Basic example
+///
+/// ```rust
+/// assert_eq!(b"Hello World!".to_vec(), fd_bs58::decode("2NEpo7TZRRrLZSi2U")?);
+/// assert_eq!(vec![0, 0], fd_bs58::decode("11")?);
+/// # Ok::<(), fd_bs58::Error>(())
+/// ```
+///
+/// ## Errors
+///
+/// ### Invalid Character
+///
+/// ```rust
+/// assert_eq!(
+///     fd_bs58::Error::InvalidCharacter { character: 'l', index: 2 },
+///     fd_bs58::decode("Hello World!").unwrap_err());
+/// ```
+///
+#[cfg(feature = "alloc")]
+pub fn decode<I: AsRef<[u8]>>(input: I) -> Result<Vec<u8>, Error> {
+    decode::decode(input.as_ref(), &Alphabet::BITCOIN)
+}
+
+/// Decodes a base58 string of any length like [`decode`], using the given alphabet.
+///
+/// # Examples
+///
+/// ## Basic example
+///
+/// ```rust
+/// use fd_bs58::Alphabet;
+///
+/// assert_eq!(vec![0, 0, 1, 2, 3, 4], fd_bs58::decode_with("rrpVC7X", &Alphabet::RIPPLE)?);
 /// # Ok::<(), fd_bs58::Error>(())
 /// ```
-pub fn encode_64<I: AsRef<[u8]>>(input: I) -> String {
-    encode_64::encode_64(input)
+#[cfg(feature = "alloc")]
+pub fn decode_with<I: AsRef<[u8]>>(input: I, alphabet: &Alphabet) -> Result<Vec<u8>, Error> {
+    decode::decode(input.as_ref(), alphabet)
 }
 
 /// Decodes the given base58 string into 32 bytes using an optimized decoding algorithm.
@@ -69,15 +795,15 @@ pub fn encode_64<I: AsRef<[u8]>>(input: I) -> String {
 ///
 /// ```rust
 /// assert_eq!(
-///     fd_bs58::Error::InvalidCharacter,
-///     fd_bs58::decode_32("l").unwrap_err());
+///     fd_bs58::Error::InvalidCharacter { character: 'l', index: 0 },
+///     fd_bs58::decode_32("lkCriyrNwS3G4rzAXtG5B1nnvb5Ka1JtCku93VqeKAr").unwrap_err());
 /// ```
 ///
 /// ### Input Too Long
 ///
 /// ```rust
 /// assert_eq!(
-///     fd_bs58::Error::InputTooLong,
+///     fd_bs58::Error::InputTooLong { max: 44, actual: 47 },
 ///     fd_bs58::decode_32("4uQeVj5tqViQh7yWWGStvkEG1Zmhx6uasJtWCJziofLRda4").unwrap_err());
 /// ```
 ///
@@ -85,8 +811,8 @@ pub fn encode_64<I: AsRef<[u8]>>(input: I) -> String {
 ///
 /// ```rust
 /// assert_eq!(
-///     fd_bs58::Error::InputTooShort,
-///     fd_bs58::decode_32("4uQeVj5tqViQh7yWWGStvkEG1Zmhx6uasJtWCJz").unwrap_err());
+///     fd_bs58::Error::InputTooShort { min: 32, actual: 31 },
+///     fd_bs58::decode_32("4uQeVj5tqViQh7yWWGStvkEG1Zmhx6u").unwrap_err());
 /// ```
 /// ### Input Byte Amount
 ///
@@ -100,6 +826,57 @@ pub fn decode_32<I: AsRef<[u8]>>(input: I) -> Result<[u8; BYTE_COUNT_32], Error>
     decode_32::decode_32(input)
 }
 
+/// Decodes the given base58 string into 32 bytes like [`decode_32`], using the given alphabet.
+///
+/// # Examples
+///
+/// ## Basic example
+///
+/// ```rust
+/// use fd_bs58::Alphabet;
+///
+/// assert_eq!([0; 32], fd_bs58::decode_32_with("rrrrrrrrrrrrrrrrrrrrrrrrrrrrrrrr", &Alphabet::RIPPLE)?);
+/// # Ok::<(), fd_bs58::Error>(())
+/// ```
+pub fn decode_32_with<I: AsRef<[u8]>>(
+    input: I,
+    alphabet: &Alphabet,
+) -> Result<[u8; BYTE_COUNT_32], Error> {
+    decode_32::decode_32_with(input, alphabet)
+}
+
+/// Decodes many base58 strings into 32 bytes, writing `inputs[i]` to `outputs[i]` and
+/// returning one result per input, the same one [`decode_32`] would give. Outputs of failed
+/// inputs are left untouched. This is a convenience wrapper that calls [`decode_32`] on each
+/// input, it isn't any faster than doing so yourself.
+///
+/// # Panics
+///
+/// Panics if `outputs` is shorter than `inputs`.
+///
+/// # Examples
+///
+/// ## Basic example
+///
+/// ```rust
+/// let inputs = ["11111111111111111111111111111111", "l"];
+/// let mut outputs = [[0; 32]; 2];
+/// let results = fd_bs58::decode_32_batch(&inputs, &mut outputs);
+/// assert_eq!(Ok(()), results[0]);
+/// assert_eq!([0; 32], outputs[0]);
+/// assert_eq!(
+///     Err(fd_bs58::Error::InvalidCharacter { character: 'l', index: 0 }),
+///     results[1]
+/// );
+/// ```
+#[cfg(feature = "alloc")]
+pub fn decode_32_batch<I: AsRef<[u8]>>(
+    inputs: &[I],
+    outputs: &mut [[u8; BYTE_COUNT_32]],
+) -> Vec<Result<(), Error>> {
+    decode_32::decode_32_batch(inputs, outputs)
+}
+
 /// Decodes the given base58 string into 64 bytes using an optimized decoding algorithm.
 /// This function will return an error if the string is not base58 encoded or the result is not 64 bytes.
 ///
@@ -120,15 +897,15 @@ pub fn decode_32<I: AsRef<[u8]>>(input: I) -> Result<[u8; BYTE_COUNT_32], Error>
 ///
 /// ```rust
 /// assert_eq!(
-///     fd_bs58::Error::InvalidCharacter,
-///     fd_bs58::decode_64("l").unwrap_err());
+///     fd_bs58::Error::InvalidCharacter { character: 'l', index: 0 },
+///     fd_bs58::decode_64("lAFv15MNPuA84RmU66xw2uMzGipcVxNpzAffoacGVvjFue3CBmf633fAWuiP9cwL9C3z3CJiGgRSFjJfeEcA6QWa").unwrap_err());
 /// ```
 ///
 /// ### Input Too Long
 ///
 /// ```rust
 /// assert_eq!(
-///     fd_bs58::Error::InputTooLong,
+///     fd_bs58::Error::InputTooLong { max: 88, actual: 90 },
 ///     fd_bs58::decode_64("2AFv15MNPuA84RmU66xw2uMzGipcVxNpzAffoacGVvjFue3CBmf633fAWuiP9cwL9C3z3CJiGgRSFjJfeEcA6QWabc").unwrap_err());
 /// ```
 ///
@@ -136,8 +913,8 @@ pub fn decode_32<I: AsRef<[u8]>>(input: I) -> Result<[u8; BYTE_COUNT_32], Error>
 ///
 /// ```rust
 /// assert_eq!(
-///     fd_bs58::Error::InputTooShort,
-///     fd_bs58::decode_64("2AFv15MNPuA84RmU66xw2uMzGipcVxNpzAffoacGVvjFue3CBmf633fAWuiP9cwL9C3z3CJiGgRSFjJfeEcA").unwrap_err());
+///     fd_bs58::Error::InputTooShort { min: 64, actual: 63 },
+///     fd_bs58::decode_64("2AFv15MNPuA84RmU66xw2uMzGipcVxNpzAffoacGVvjFue3CBmf633fAWuiP9cw").unwrap_err());
 /// ```
 /// ### Input Byte Amount
 ///
@@ -151,14 +928,536 @@ pub fn decode_64<I: AsRef<[u8]>>(input: I) -> Result<[u8; BYTE_COUNT_64], Error>
     decode_64::decode_64(input)
 }
 
+/// Decodes the given base58 string into 64 bytes like [`decode_64`], using the given alphabet.
+///
+/// # Examples
+///
+/// ## Basic example
+///
+/// ```rust
+/// use fd_bs58::Alphabet;
+///
+/// assert_eq!([0; 64], fd_bs58::decode_64_with("rrrrrrrrrrrrrrrrrrrrrrrrrrrrrrrrrrrrrrrrrrrrrrrrrrrrrrrrrrrrrrrr", &Alphabet::RIPPLE)?);
+/// # Ok::<(), fd_bs58::Error>(())
+/// ```
+pub fn decode_64_with<I: AsRef<[u8]>>(
+    input: I,
+    alphabet: &Alphabet,
+) -> Result<[u8; BYTE_COUNT_64], Error> {
+    decode_64::decode_64_with(input, alphabet)
+}
+
+/// Decodes many base58 strings into 64 bytes, writing `inputs[i]` to `outputs[i]` and
+/// returning one result per input, the same one [`decode_64`] would give. Outputs of failed
+/// inputs are left untouched. This is a convenience wrapper that calls [`decode_64`] on each
+/// input, it isn't any faster than doing so yourself.
+///
+/// # Panics
+///
+/// Panics if `outputs` is shorter than `inputs`.
+///
+/// # Examples
+///
+/// ## Basic example
+///
+/// ```rust
+/// let inputs = ["1111111111111111111111111111111111111111111111111111111111111111", "l"];
+/// let mut outputs = [[0; 64]; 2];
+/// let results = fd_bs58::decode_64_batch(&inputs, &mut outputs);
+/// assert_eq!(Ok(()), results[0]);
+/// assert_eq!([0; 64], outputs[0]);
+/// assert_eq!(
+///     Err(fd_bs58::Error::InvalidCharacter { character: 'l', index: 0 }),
+///     results[1]
+/// );
+/// ```
+#[cfg(feature = "alloc")]
+pub fn decode_64_batch<I: AsRef<[u8]>>(
+    inputs: &[I],
+    outputs: &mut [[u8; BYTE_COUNT_64]],
+) -> Vec<Result<(), Error>> {
+    decode_64::decode_64_batch(inputs, outputs)
+}
+
+/// Decodes the given base58 string into `N` bytes using the same optimized algorithm as
+/// [`decode_32`] and [`decode_64`], for any `N` from 1 to [`constants::MAX_FIXED_BYTE_COUNT`].
+/// This function will return an error if the string is not base58 encoded or the result is not `N` bytes.
+///
+/// # Examples
+///
+/// ## Basic example
+///
+/// ```rust
+/// // a compressed secp256k1 public key
+/// let key: [u8; 33] = fd_bs58::decode_fixed("gsy56ZagSdvNvfb5rTbFw7krBLZfihAbaoQFpxnrjoSq")?;
+/// assert_eq!([0x02, 0x50, 0x86, 0x3a], key[..4]);
+/// # Ok::<(), fd_bs58::Error>(())
+/// ```
+///
+/// ## Errors
+///
+/// ### Input Byte Amount
+///
+/// ```rust
+/// assert_eq!(
+///     fd_bs58::Error::InvalidByteAmount,
+///     fd_bs58::decode_fixed::<20>("4ZrjxJnU1LA5xSyrWMNuXTvSYKwu").unwrap_err());
+/// ```
+///
+pub fn decode_fixed<const N: usize>(input: impl AsRef<[u8]>) -> Result<[u8; N], Error> {
+    decode_fixed::decode_fixed_fast(input.as_ref(), &Alphabet::BITCOIN)
+}
+
+/// Decodes the given base58 string into 32 bytes like [`decode_32`], but in constant time:
+/// neither the time it takes nor the memory it touches depends on the characters, only on how
+/// many there are, which makes it suitable for secret keys. Returns the same errors as
+/// [`decode_32`]. This is slower than [`decode_32`] and never uses the SIMD code paths.
+///
+/// # Examples
+///
+/// ## Basic example
+///
+/// ```rust
+/// let key = "XkCriyrNwS3G4rzAXtG5B1nnvb5Ka1JtCku93VqeKAr";
+/// assert_eq!(fd_bs58::decode_32(key)?, fd_bs58::decode_32_ct(key)?);
+/// # Ok::<(), fd_bs58::Error>(())
+/// ```
+///
+/// ## Errors
+///
+/// ### Invalid Character
+///
+/// ```rust
+/// assert_eq!(
+///     fd_bs58::Error::InvalidCharacter { character: 'l', index: 1 },
+///     fd_bs58::decode_32_ct("1l111111111111111111111111111111").unwrap_err());
+/// ```
+pub fn decode_32_ct<I: AsRef<[u8]>>(input: I) -> Result<[u8; BYTE_COUNT_32], Error> {
+    constant_time::decode_fixed_ct(input.as_ref(), &Alphabet::BITCOIN)
+}
+
+/// Decodes the given base58 string into 64 bytes like [`decode_64`], but in constant time.
+/// See [`decode_32_ct`].
+///
+/// # Examples
+///
+/// ## Basic example
+///
+/// ```rust
+/// let keypair = "11cgTH4D5e8S3snD444WbbGrkepjTvWMj2jkmCGJtgn3H7qrPb1BnwapxpbGdRtHQh9t9Wbn9t6ZDGHzWpL4df";
+/// assert_eq!(fd_bs58::decode_64(keypair)?, fd_bs58::decode_64_ct(keypair)?);
+/// # Ok::<(), fd_bs58::Error>(())
+/// ```
+pub fn decode_64_ct<I: AsRef<[u8]>>(input: I) -> Result<[u8; BYTE_COUNT_64], Error> {
+    constant_time::decode_fixed_ct(input.as_ref(), &Alphabet::BITCOIN)
+}
+
+/// Decodes the given base58 string into 32 bytes like [`decode_32_ct`], returning bytes that
+/// are wiped when dropped. Along with the `zeroize` feature wiping every buffer the decoder
+/// works in, this leaves no copy of a secret key behind.
+///
+/// # Examples
+///
+/// ## Basic example
+///
+/// ```rust
+/// let secret = fd_bs58::decode_32_secret("XkCriyrNwS3G4rzAXtG5B1nnvb5Ka1JtCku93VqeKAr")?;
+/// assert_eq!([7, 224, 70, 147], secret[..4]);
+/// # Ok::<(), fd_bs58::Error>(())
+/// ```
+#[cfg(feature = "zeroize")]
+pub fn decode_32_secret<I: AsRef<[u8]>>(input: I) -> Result<Zeroizing<[u8; BYTE_COUNT_32]>, Error> {
+    let mut out = Zeroizing::new([0; BYTE_COUNT_32]);
+    constant_time::decode_fixed_ct_into(input.as_ref(), &mut out, &Alphabet::BITCOIN)?;
+    Ok(out)
+}
+
+/// Decodes the given base58 string into 64 bytes, such as an Ed25519 keypair, like
+/// [`decode_64_ct`], returning bytes that are wiped when dropped. See [`decode_32_secret`].
+///
+/// # Examples
+///
+/// ## Basic example
+///
+/// ```rust
+/// let keypair = fd_bs58::decode_64_secret("11cgTH4D5e8S3snD444WbbGrkepjTvWMj2jkmCGJtgn3H7qrPb1BnwapxpbGdRtHQh9t9Wbn9t6ZDGHzWpL4df")?;
+/// assert_eq!([0, 0, 10, 85], keypair[..4]);
+/// # Ok::<(), fd_bs58::Error>(())
+/// ```
+#[cfg(feature = "zeroize")]
+pub fn decode_64_secret<I: AsRef<[u8]>>(input: I) -> Result<Zeroizing<[u8; BYTE_COUNT_64]>, Error> {
+    let mut out = Zeroizing::new([0; BYTE_COUNT_64]);
+    constant_time::decode_fixed_ct_into(input.as_ref(), &mut out, &Alphabet::BITCOIN)?;
+    Ok(out)
+}
+
+/// Decodes the given base58 string into 32 bytes like [`decode_32`], as a `const fn`. It is
+/// much slower than [`decode_32`], so it is meant for constants, see [`b58!`] for a shorthand.
+///
+/// # Examples
+///
+/// ## Basic example
+///
+/// ```rust
+/// const KEY: [u8; 32] = match fd_bs58::decode_32_const("XkCriyrNwS3G4rzAXtG5B1nnvb5Ka1JtCku93VqeKAr") {
+///     Ok(key) => key,
+///     Err(_) => panic!("invalid key"),
+/// };
+/// assert_eq!(fd_bs58::decode_32("XkCriyrNwS3G4rzAXtG5B1nnvb5Ka1JtCku93VqeKAr")?, KEY);
+/// # Ok::<(), fd_bs58::Error>(())
+/// ```
+///
+/// ## Errors
+///
+/// The same as [`decode_32`].
+///
+/// ```rust
+/// assert_eq!(
+///     fd_bs58::Error::InvalidCharacter { character: 'l', index: 0 },
+///     fd_bs58::decode_32_const("l1111111111111111111111111111111").unwrap_err());
+/// ```
+pub const fn decode_32_const(input: &str) -> Result<[u8; BYTE_COUNT_32], Error> {
+    decode_32::decode_32_const(input)
+}
+
+/// Decodes the given base58 string into 64 bytes like [`decode_64`], as a `const fn`. It is
+/// much slower than [`decode_64`], so it is meant for constants, see [`b58_64!`] for a
+/// shorthand.
+///
+/// # Examples
+///
+/// ## Basic example
+///
+/// ```rust
+/// const SIGNATURE: Result<[u8; 64], fd_bs58::Error> = fd_bs58::decode_64_const("1111111111111111111111111111111111111111111111111111111111111111");
+/// assert_eq!(Ok([0; 64]), SIGNATURE);
+/// ```
+pub const fn decode_64_const(input: &str) -> Result<[u8; BYTE_COUNT_64], Error> {
+    decode_64::decode_64_const(input)
+}
+
+/// Decodes a base58 literal into a `[u8; 32]` at compile time, so well known keys such as
+/// program IDs can be written out in base58. Invalid literals don't compile.
+///
+/// # Examples
+///
+/// ## Basic example
+///
+/// ```rust
+/// const TOKEN_PROGRAM: [u8; 32] = fd_bs58::b58!("TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA");
+/// assert_eq!(fd_bs58::decode_32("TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA")?, TOKEN_PROGRAM);
+/// # Ok::<(), fd_bs58::Error>(())
+/// ```
+///
+/// ## Errors
+///
+/// ```rust,compile_fail
+/// // 'l' is not in the alphabet
+/// const KEY: [u8; 32] = fd_bs58::b58!("TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5Dl");
+/// ```
+#[macro_export]
+macro_rules! b58 {
+    ($encoded:expr) => {{
+        const DECODED: [u8; 32] = $crate::__unwrap_literal($crate::decode_32_const($encoded));
+        DECODED
+    }};
+}
+
+/// Decodes a base58 literal into a `[u8; 64]` at compile time, like [`b58!`].
+///
+/// # Examples
+///
+/// ## Basic example
+///
+/// ```rust
+/// const ZERO: [u8; 64] = fd_bs58::b58_64!("1111111111111111111111111111111111111111111111111111111111111111");
+/// assert_eq!([0; 64], ZERO);
+/// ```
+///
+/// ## Errors
+///
+/// ```rust,compile_fail
+/// // 32 bytes are too short
+/// const SIGNATURE: [u8; 64] = fd_bs58::b58_64!("TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA");
+/// ```
+#[macro_export]
+macro_rules! b58_64 {
+    ($encoded:expr) => {{
+        const DECODED: [u8; 64] = $crate::__unwrap_literal($crate::decode_64_const($encoded));
+        DECODED
+    }};
+}
+
+/// Turns a decoding error into a compile error for [`b58!`] and [`b58_64!`]. Panic messages
+/// in const context can't be formatted, hence one per variant.
+#[doc(hidden)]
+pub const fn __unwrap_literal<const N: usize>(result: Result<[u8; N], Error>) -> [u8; N] {
+    match result {
+        Ok(decoded) => decoded,
+        Err(Error::InvalidCharacter { .. }) => panic!("invalid base58 character in literal"),
+        Err(Error::InputTooLong { .. }) => panic!("base58 literal is too long"),
+        Err(Error::InputTooShort { .. }) => panic!("base58 literal is too short"),
+        Err(Error::InvalidByteAmount) => panic!("base58 literal does not fit the byte size"),
+        Err(_) => panic!("invalid base58 literal"),
+    }
+}
+
+/// Encodes the version byte and payload with a 4 byte double SHA-256 checksum appended,
+/// as used by Bitcoin addresses and WIF private keys. Addresses and private keys use the same
+/// optimized algorithm as [`encode_32`] and [`encode_64`].
+///
+/// # Examples
+///
+/// ## Basic example
+///
+/// ```rust
+/// // the Bitcoin genesis block address
+/// let hash = [0x62, 0xe9, 0x07, 0xb1, 0x5c, 0xbf, 0x27, 0xd5, 0x42, 0x53, 0x99, 0xeb, 0xf6, 0xf0, 0xfb, 0x50, 0xeb, 0xb8, 0x8f, 0x18];
+/// assert_eq!("1A1zP1eP5QGefi2DMPTfTL5SLmv7DivfNa", fd_bs58::encode_check(0x00, hash));
+/// ```
+#[cfg(feature = "check")]
+pub fn encode_check<I: AsRef<[u8]>>(version: u8, payload: I) -> String {
+    check::encode_check(version, payload.as_ref())
+}
+
+/// Decodes a Base58Check string into its version byte and payload after verifying the checksum.
+/// This function will return an error if the string is not base58 encoded, is too short to
+/// hold a version byte and checksum, or the checksum does not match.
+///
+/// # Examples
+///
+/// ## Basic example
+///
+/// ```rust
+/// // a compressed WIF private key
+/// let (version, payload) = fd_bs58::decode_check("KwdMAjGmerYanjeui5SHS7JkmpZvVipYvB2LJGU1ZxJwYvP98617")?;
+/// assert_eq!(0x80, version);
+/// assert_eq!([0x0c, 0x28, 0xfc, 0xa3], payload[..4]);
+/// assert_eq!(0x01, payload[32]);
+/// # Ok::<(), fd_bs58::Error>(())
+/// ```
+///
+/// ## Errors
+///
+/// ### Invalid Checksum
+///
+/// ```rust
+/// use fd_bs58::checksum::ChecksumBytes;
+///
+/// assert_eq!(
+///     fd_bs58::Error::InvalidChecksum {
+///         expected: ChecksumBytes::new(&[0xc2, 0x9b, 0x7d, 0x93]),
+///         found: ChecksumBytes::new(&[0xc2, 0x9b, 0x7d, 0x94]),
+///     },
+///     fd_bs58::decode_check("1A1zP1eP5QGefi2DMPTfTL5SLmv7DivfNb").unwrap_err());
+/// ```
+///
+/// ### Input Too Short
+///
+/// ```rust
+/// assert_eq!(
+///     fd_bs58::Error::InputTooShort { min: 5, actual: 4 },
+///     fd_bs58::decode_check("1111").unwrap_err());
+/// ```
+///
+#[cfg(feature = "check")]
+pub fn decode_check<I: AsRef<[u8]>>(input: I) -> Result<(u8, Vec<u8>), Error> {
+    check::decode_check(input.as_ref())
+}
+
+/// Decodes a Base58Check string like [`decode_check`] and also checks the version byte.
+///
+/// # Examples
+///
+/// ## Basic example
+///
+/// ```rust
+/// let hash = fd_bs58::decode_check_with_version("1A1zP1eP5QGefi2DMPTfTL5SLmv7DivfNa", 0x00)?;
+/// assert_eq!(20, hash.len());
+/// # Ok::<(), fd_bs58::Error>(())
+/// ```
+///
+/// ## Errors
+///
+/// ### Invalid Version
+///
+/// ```rust
+/// assert_eq!(
+///     fd_bs58::Error::InvalidVersion { expected: 0x80, found: 0x00 },
+///     fd_bs58::decode_check_with_version("1A1zP1eP5QGefi2DMPTfTL5SLmv7DivfNa", 0x80).unwrap_err());
+/// ```
+///
+#[cfg(feature = "check")]
+pub fn decode_check_with_version<I: AsRef<[u8]>>(input: I, version: u8) -> Result<Vec<u8>, Error> {
+    check::decode_check_with_version(input.as_ref(), version)
+}
+
+/// Encodes the payload with the checksum of the given [`ChecksumScheme`] appended.
+///
+/// # Examples
+///
+/// ## Basic example
+///
+/// ```rust
+/// # #[cfg(feature = "checksum-sha256d")] {
+/// use fd_bs58::checksum::DoubleSha256;
+///
+/// // version 0 and the hash160 of the Bitcoin genesis block's public key
+/// let data = [0, 98, 233, 7, 177, 92, 191, 39, 213, 66, 83, 153, 235, 246, 240, 251, 80, 235, 184, 143, 24];
+/// assert_eq!("1A1zP1eP5QGefi2DMPTfTL5SLmv7DivfNa", fd_bs58::encode_checked::<DoubleSha256>(data));
+/// # }
+/// ```
+///
+/// [`ChecksumScheme`]: checksum::ChecksumScheme
+#[cfg(feature = "alloc")]
+pub fn encode_checked<S: checksum::ChecksumScheme>(payload: impl AsRef<[u8]>) -> String {
+    checksum::encode_checked::<S>(payload.as_ref())
+}
+
+/// Decodes a base58 string and verifies and removes the checksum of the given
+/// [`ChecksumScheme`] at the end.
+///
+/// # Errors
+///
+/// [`Error::InvalidCharacter`] if the input isn't base58, [`Error::InputTooShort`] if it
+/// has fewer characters than the checksum has bytes, [`Error::InvalidByteAmount`] if it
+/// decodes to fewer bytes than the checksum anyway, and [`Error::InvalidChecksum`] if the
+/// checksum does not match.
+///
+/// # Examples
+///
+/// ## Basic example
+///
+/// ```rust
+/// # #[cfg(feature = "checksum-ss58")] {
+/// use fd_bs58::{checksum::Ss58, Error};
+///
+/// let data = fd_bs58::decode_checked::<Ss58>("5GrwvaEF5zXb26Fz9rcQpDWS57CtERHpNehXCPcNoHGKutQY")?;
+/// assert_eq!(42, data[0]);
+/// assert_eq!(33, data.len());
+///
+/// assert!(matches!(
+///     fd_bs58::decode_checked::<Ss58>("5GrwvaEF5zXb26Fz9rcQpDWS57CtERHpNehXCPcNoHGKutQZ"),
+///     Err(Error::InvalidChecksum { .. })));
+/// # }
+/// # Ok::<(), fd_bs58::Error>(())
+/// ```
+///
+/// [`ChecksumScheme`]: checksum::ChecksumScheme
+#[cfg(feature = "alloc")]
+pub fn decode_checked<S: checksum::ChecksumScheme>(
+    input: impl AsRef<[u8]>,
+) -> Result<Vec<u8>, Error> {
+    checksum::decode_checked::<S>(input.as_ref())
+}
+
 #[derive(Debug, PartialEq)]
 pub enum Error {
-    /// The input contains an invalid character
-    InvalidCharacter,
-    /// The input is too long
-    InputTooLong,
-    /// The input is too long
-    InputTooShort,
-    /// The decoded base58 array does not fit the expected byte size
+    /// The input contains `character`, which is not in the alphabet, at byte offset `index`
+    InvalidCharacter { character: char, index: usize },
+    /// The input has `actual` characters, more than the longest valid encoding's `max`
+    InputTooLong { max: usize, actual: usize },
+    /// The input has `actual` characters, fewer than the shortest valid encoding's `min`
+    InputTooShort { min: usize, actual: usize },
+    /// The input has a valid length but does not encode the expected number of bytes: the
+    /// value is too big, or its leading '1's don't match the leading zero bytes
     InvalidByteAmount,
+    /// The input to be encoded does not have the expected number of bytes
+    InvalidInputLength { expected: usize, actual: usize },
+    /// The checksum does not match the decoded data
+    InvalidChecksum {
+        expected: checksum::ChecksumBytes,
+        found: checksum::ChecksumBytes,
+    },
+    /// The Base58Check version byte is not the expected one
+    InvalidVersion { expected: u8, found: u8 },
+}
+
+impl Error {
+    /// For an [`Error::InvalidCharacter`] that is commonly mistaken for a valid one, such as
+    /// `'0'` for `'o'` or `'l'` for `'1'`, returns the character that was probably meant, if
+    /// it is part of `alphabet`.
+    ///
+    /// # Examples
+    ///
+    /// ## Basic example
+    ///
+    /// ```rust
+    /// use fd_bs58::Alphabet;
+    ///
+    /// let error = fd_bs58::decode_32("l1111111111111111111111111111111").unwrap_err();
+    /// assert_eq!(Some('1'), error.suggestion(&Alphabet::BITCOIN));
+    ///
+    /// // 'o' isn't part of this alphabet, so 'O' has no suggestion
+    /// let alphabet = Alphabet::new(b"0123456789abcdefghijkmnpqrstuvwxyzABCDEFGHJKLMNPQRSTUVWXYZ")
+    ///     .unwrap();
+    /// let error = fd_bs58::decode_with("O1", &alphabet).unwrap_err();
+    /// assert_eq!(None, error.suggestion(&alphabet));
+    /// ```
+    pub fn suggestion(&self, alphabet: &Alphabet) -> Option<char> {
+        let suggestion = match self {
+            Error::InvalidCharacter { character, .. } => match character {
+                '0' | 'O' => 'o',
+                'I' | 'l' => '1',
+                _ => return None,
+            },
+            _ => return None,
+        };
+        if alphabet.decode[suggestion as usize] == constants::BASE58_INVALID_CHAR {
+            return None;
+        }
+        Some(suggestion)
+    }
+
+    /// Builds an [`Error::InvalidCharacter`] for the byte at `index`, reporting the whole
+    /// UTF-8 character it starts if there is one.
+    pub(crate) fn invalid_character(input: &[u8], index: usize) -> Error {
+        let bytes = &input[index..input.len().min(index + 4)];
+        let valid = match core::str::from_utf8(bytes) {
+            Ok(valid) => valid,
+            Err(e) => core::str::from_utf8(&bytes[..e.valid_up_to()]).unwrap(),
+        };
+        Error::InvalidCharacter {
+            character: valid.chars().next().unwrap_or(char::REPLACEMENT_CHARACTER),
+            index,
+        }
+    }
+}
+
+impl core::fmt::Display for Error {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            Error::InvalidCharacter { character, index } => {
+                write!(f, "invalid base58 character {character:?} at index {index}")
+            }
+            Error::InputTooLong { max, actual } => write!(
+                f,
+                "base58 input is too long, expected at most {max} characters, got {actual}"
+            ),
+            Error::InputTooShort { min, actual } => write!(
+                f,
+                "base58 input is too short, expected at least {min} characters, got {actual}"
+            ),
+            Error::InvalidByteAmount => {
+                f.write_str("decoded base58 value does not fit the expected byte size")
+            }
+            Error::InvalidInputLength { expected, actual } => {
+                write!(f, "expected {expected} input bytes, got {actual}")
+            }
+            Error::InvalidChecksum { expected, found } => {
+                write!(
+                    f,
+                    "invalid checksum, expected {expected:02x?}, found {found:02x?}"
+                )
+            }
+            Error::InvalidVersion { expected, found } => {
+                write!(
+                    f,
+                    "invalid version, expected {expected:#04x}, found {found:#04x}"
+                )
+            }
+        }
+    }
 }
+
+impl core::error::Error for Error {}
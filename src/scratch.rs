@@ -0,0 +1,49 @@
+//! The stack buffers the codecs work in hold a reconstruction of whatever is being encoded or
+//! decoded, which may be a secret key. With the `zeroize` feature they are wrapped in
+//! [`Zeroizing`](zeroize::Zeroizing) so they are wiped when dropped, early returns included.
+//! Otherwise [`Scratch`] is a plain wrapper that compiles away.
+
+use core::mem::MaybeUninit;
+
+#[cfg(feature = "zeroize")]
+pub(crate) use zeroize::Zeroizing as Scratch;
+
+#[cfg(not(feature = "zeroize"))]
+pub(crate) struct Scratch<T>(T);
+
+#[cfg(not(feature = "zeroize"))]
+impl<T> Scratch<T> {
+    #[inline(always)]
+    pub(crate) fn new(value: T) -> Self {
+        Scratch(value)
+    }
+}
+
+#[cfg(not(feature = "zeroize"))]
+impl<T> core::ops::Deref for Scratch<T> {
+    type Target = T;
+
+    #[inline(always)]
+    fn deref(&self) -> &T {
+        &self.0
+    }
+}
+
+#[cfg(not(feature = "zeroize"))]
+impl<T> core::ops::DerefMut for Scratch<T> {
+    #[inline(always)]
+    fn deref_mut(&mut self) -> &mut T {
+        &mut self.0
+    }
+}
+
+/// Sets the first `len` elements of `buf` to `value` and returns them.  The buffers are sized
+/// for the widest codec, so narrower ones don't pay for clearing the part they don't use.
+#[inline(always)]
+pub(crate) fn init_prefix<T: Copy>(buf: &mut [MaybeUninit<T>], len: usize, value: T) -> &mut [T] {
+    let prefix = &mut buf[..len];
+    prefix.fill(MaybeUninit::new(value));
+    // SAFETY: every element of prefix was just initialized, and MaybeUninit<T> has the same
+    // layout as T
+    unsafe { &mut *(prefix as *mut [MaybeUninit<T>] as *mut [T]) }
+}
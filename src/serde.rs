@@ -0,0 +1,344 @@
+//! Adapters for `#[serde(with = "...")]` that store bytes as base58 strings in human readable
+//! formats such as JSON, and as plain bytes in binary formats such as bincode.
+//!
+//! Strings are decoded straight from what the deserializer hands out, so nothing is copied
+//! into an intermediate `String`.
+//!
+//! # Examples
+//!
+//! ## Basic example
+//!
+//! ```rust
+//! #[derive(serde::Serialize, serde::Deserialize)]
+//! struct Transfer {
+//!     #[serde(with = "fd_bs58::serde::b58_32")]
+//!     to: [u8; 32],
+//!     #[serde(with = "fd_bs58::serde::b58")]
+//!     memo: Vec<u8>,
+//! }
+//!
+//! let json = r#"{"to":"XkCriyrNwS3G4rzAXtG5B1nnvb5Ka1JtCku93VqeKAr","memo":"2NEpo7TZRRrLZSi2U"}"#;
+//! let transfer: Transfer = serde_json::from_str(json)?;
+//! assert_eq!([7, 224, 70, 147], transfer.to[..4]);
+//! assert_eq!(b"Hello World!", transfer.memo.as_slice());
+//! assert_eq!(json, serde_json::to_string(&transfer)?);
+//! # Ok::<(), serde_json::Error>(())
+//! ```
+
+use core::fmt;
+
+use ::serde::{
+    de::{self, SeqAccess, Visitor},
+    Deserialize, Deserializer, Serialize, Serializer,
+};
+
+use crate::{
+    alphabet::Alphabet, array::Base58Array, decode_fixed::decode_fixed_fast,
+    encode_fixed::encode_fixed_into_fast, fixed::MAX_ENCODED_LEN, scratch::Scratch,
+    string::Base58Inline,
+};
+
+pub(crate) fn serialize_fixed<const N: usize, S: Serializer>(
+    bytes: &[u8; N],
+    alphabet: &Alphabet,
+    serializer: S,
+) -> Result<S::Ok, S::Error> {
+    if serializer.is_human_readable() {
+        let mut buf = Scratch::new([0; MAX_ENCODED_LEN]);
+        let len = encode_fixed_into_fast(bytes, &mut buf[..], alphabet);
+        // SAFETY: every byte written by the encoders comes from an ASCII base58 alphabet
+        serializer.serialize_str(unsafe { core::str::from_utf8_unchecked(&buf[..len]) })
+    } else {
+        serializer.serialize_bytes(bytes)
+    }
+}
+
+pub(crate) fn deserialize_fixed<'de, const N: usize, D: Deserializer<'de>>(
+    deserializer: D,
+    alphabet: &Alphabet,
+) -> Result<[u8; N], D::Error> {
+    if deserializer.is_human_readable() {
+        deserializer.deserialize_str(FixedVisitor::<N>(alphabet))
+    } else {
+        deserializer.deserialize_bytes(FixedVisitor::<N>(alphabet))
+    }
+}
+
+struct FixedVisitor<'a, const N: usize>(&'a Alphabet);
+
+impl<'de, const N: usize> Visitor<'de> for FixedVisitor<'_, N> {
+    type Value = [u8; N];
+
+    fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "a base58 string or {N} bytes")
+    }
+
+    fn visit_str<E: de::Error>(self, v: &str) -> Result<[u8; N], E> {
+        decode_fixed_fast(v.as_bytes(), self.0).map_err(E::custom)
+    }
+
+    fn visit_bytes<E: de::Error>(self, v: &[u8]) -> Result<[u8; N], E> {
+        v.try_into().map_err(|_| E::invalid_length(v.len(), &self))
+    }
+
+    fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<[u8; N], A::Error> {
+        let mut out = [0; N];
+        for (i, byte) in out.iter_mut().enumerate() {
+            *byte = seq
+                .next_element()?
+                .ok_or_else(|| de::Error::invalid_length(i, &self))?;
+        }
+        if seq.next_element::<u8>()?.is_some() {
+            return Err(de::Error::invalid_length(N + 1, &self));
+        }
+        Ok(out)
+    }
+}
+
+/// Serializes `[u8; 32]` as base58 with [`encode_32`](crate::encode_32) and deserializes it
+/// with [`decode_32`](crate::decode_32).
+pub mod b58_32 {
+    use ::serde::{Deserializer, Serializer};
+
+    use crate::{alphabet::Alphabet, constants::BYTE_COUNT_32};
+
+    pub fn serialize<S: Serializer>(
+        bytes: &[u8; BYTE_COUNT_32],
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        super::serialize_fixed(bytes, &Alphabet::BITCOIN, serializer)
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<[u8; BYTE_COUNT_32], D::Error> {
+        super::deserialize_fixed(deserializer, &Alphabet::BITCOIN)
+    }
+}
+
+/// Serializes `[u8; 64]` as base58 with [`encode_64`](crate::encode_64) and deserializes it
+/// with [`decode_64`](crate::decode_64).
+pub mod b58_64 {
+    use ::serde::{Deserializer, Serializer};
+
+    use crate::{alphabet::Alphabet, constants::BYTE_COUNT_64};
+
+    pub fn serialize<S: Serializer>(
+        bytes: &[u8; BYTE_COUNT_64],
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        super::serialize_fixed(bytes, &Alphabet::BITCOIN, serializer)
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<[u8; BYTE_COUNT_64], D::Error> {
+        super::deserialize_fixed(deserializer, &Alphabet::BITCOIN)
+    }
+}
+
+/// Serializes bytes of any length as base58 with [`encode`](crate::encode) and deserializes
+/// them into a `Vec<u8>` with [`decode`](crate::decode).
+#[cfg(feature = "alloc")]
+pub mod b58 {
+    use core::fmt;
+
+    use alloc::vec::Vec;
+
+    use ::serde::{
+        de::{self, SeqAccess, Visitor},
+        Deserializer, Serializer,
+    };
+
+    use crate::{alphabet::Alphabet, decode::decode, encode::encode};
+
+    pub fn serialize<T: AsRef<[u8]> + ?Sized, S: Serializer>(
+        bytes: &T,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        if serializer.is_human_readable() {
+            serializer.serialize_str(&encode(bytes.as_ref(), &Alphabet::BITCOIN))
+        } else {
+            serializer.serialize_bytes(bytes.as_ref())
+        }
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Vec<u8>, D::Error> {
+        if deserializer.is_human_readable() {
+            deserializer.deserialize_str(VecVisitor)
+        } else {
+            deserializer.deserialize_bytes(VecVisitor)
+        }
+    }
+
+    struct VecVisitor;
+
+    impl<'de> Visitor<'de> for VecVisitor {
+        type Value = Vec<u8>;
+
+        fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+            f.write_str("a base58 string or bytes")
+        }
+
+        fn visit_str<E: de::Error>(self, v: &str) -> Result<Vec<u8>, E> {
+            decode(v.as_bytes(), &Alphabet::BITCOIN).map_err(E::custom)
+        }
+
+        fn visit_bytes<E: de::Error>(self, v: &[u8]) -> Result<Vec<u8>, E> {
+            Ok(v.to_vec())
+        }
+
+        fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<Vec<u8>, A::Error> {
+            let mut out = Vec::with_capacity(seq.size_hint().unwrap_or(0));
+            while let Some(byte) = seq.next_element()? {
+                out.push(byte);
+            }
+            Ok(out)
+        }
+    }
+}
+
+impl<const N: usize> Serialize for Base58Array<N> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serialize_fixed(&self.0, &Alphabet::BITCOIN, serializer)
+    }
+}
+
+impl<'de, const N: usize> Deserialize<'de> for Base58Array<N> {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        deserialize_fixed(deserializer, &Alphabet::BITCOIN).map(Base58Array)
+    }
+}
+
+impl<const N: usize> Serialize for Base58Inline<N> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(self)
+    }
+}
+
+impl<'de, const N: usize> Deserialize<'de> for Base58Inline<N> {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        deserializer.deserialize_str(InlineVisitor::<N>)
+    }
+}
+
+struct InlineVisitor<const N: usize>;
+
+impl<'de, const N: usize> Visitor<'de> for InlineVisitor<N> {
+    type Value = Base58Inline<N>;
+
+    fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "a base58 string of at most {N} characters")
+    }
+
+    fn visit_str<E: de::Error>(self, v: &str) -> Result<Base58Inline<N>, E> {
+        Base58Inline::new(v).map_err(E::custom)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde::{Deserialize, Serialize};
+
+    use crate::{Base58Inline, Bs58Bytes64};
+
+    #[derive(Debug, PartialEq, Serialize, Deserialize)]
+    struct Fixed {
+        #[serde(with = "super::b58_32")]
+        key: [u8; 32],
+        #[serde(with = "super::b58_64")]
+        signature: [u8; 64],
+        typed: Bs58Bytes64,
+    }
+
+    #[test]
+    fn test_fixed() {
+        let value = Fixed {
+            key: [7; 32],
+            signature: [255; 64],
+            typed: Bs58Bytes64::from([0; 64]),
+        };
+
+        let json = serde_json::to_string(&value).unwrap();
+        assert_eq!(
+            json,
+            format!(
+                r#"{{"key":"{}","signature":"{}","typed":"{}"}}"#,
+                bs58::encode([7; 32]).into_string(),
+                bs58::encode([255; 64]).into_string(),
+                "1".repeat(64)
+            )
+        );
+        assert_eq!(serde_json::from_str::<Fixed>(&json).unwrap(), value);
+
+        let binary = bincode::serialize(&value).unwrap();
+        // each field is a u64 length prefix and the raw bytes
+        assert_eq!(binary.len(), 3 * 8 + 32 + 64 + 64);
+        assert_eq!(bincode::deserialize::<Fixed>(&binary).unwrap(), value);
+    }
+
+    #[test]
+    fn test_fixed_errors() {
+        let json = format!("{:?}", "l".repeat(64));
+        let error = serde_json::from_str::<Bs58Bytes64>(&json).unwrap_err();
+        assert!(error
+            .to_string()
+            .starts_with("invalid base58 character 'l' at index 0"));
+
+        // bincode writes a Vec<u8> the same way as bytes
+        let binary = bincode::serialize(&vec![0u8; 63]).unwrap();
+        assert!(bincode::deserialize::<Bs58Bytes64>(&binary).is_err());
+    }
+
+    #[test]
+    fn test_inline() {
+        let key = crate::encode_32_inline(&[7; 32]);
+        let json = serde_json::to_string(&key).unwrap();
+        assert_eq!(json, format!(r#""{key}""#));
+        assert_eq!(
+            serde_json::from_str::<Base58Inline<44>>(&json).unwrap(),
+            key
+        );
+
+        let binary = bincode::serialize(&key).unwrap();
+        assert_eq!(binary.len(), 8 + key.len());
+        assert_eq!(
+            bincode::deserialize::<Base58Inline<44>>(&binary).unwrap(),
+            key
+        );
+
+        let long = format!(r#""{}""#, "1".repeat(45));
+        assert!(serde_json::from_str::<Base58Inline<44>>(&long).is_err());
+        assert!(serde_json::from_str::<Base58Inline<44>>(r#""0""#).is_err());
+    }
+
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn test_variable() {
+        #[derive(Debug, PartialEq, Serialize, Deserialize)]
+        struct Memo {
+            #[serde(with = "super::b58")]
+            memo: Vec<u8>,
+        }
+
+        for len in [0, 1, 20, 32, 64, 100] {
+            let value = Memo {
+                memo: (0..len).map(|i| i as u8).collect(),
+            };
+
+            let json = serde_json::to_string(&value).unwrap();
+            assert_eq!(
+                json,
+                format!(
+                    r#"{{"memo":"{}"}}"#,
+                    bs58::encode(&value.memo).into_string()
+                )
+            );
+            assert_eq!(serde_json::from_str::<Memo>(&json).unwrap(), value);
+
+            let binary = bincode::serialize(&value).unwrap();
+            assert_eq!(binary.len(), 8 + len);
+            assert_eq!(bincode::deserialize::<Memo>(&binary).unwrap(), value);
+        }
+    }
+}
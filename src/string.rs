@@ -0,0 +1,715 @@
+use core::{
+    borrow::Borrow,
+    cmp::Ordering,
+    fmt,
+    hash::{Hash, Hasher},
+    ops::Deref,
+    str::FromStr,
+};
+
+#[cfg(feature = "alloc")]
+use alloc::{borrow::ToOwned, boxed::Box, string::String};
+
+use crate::{
+    constants::{
+        BASE58_INVALID_CHAR, BASE58_INVERSE, BASE58_INVERSE_TABLE_OFFSET,
+        BASE58_INVERSE_TABLE_SENTINEL, BYTE_COUNT_32, BYTE_COUNT_64,
+    },
+    decode_fixed::decode_fixed_validated,
+    Error,
+};
+
+/// A string slice that only contains characters of the Bitcoin base58 alphabet. It is to
+/// [`Base58String`] what `str` is to `String`.
+///
+/// The only way to get one is through [`Base58Str::new`] or a [`Base58String`], both of
+/// which check every character, so code taking a `&Base58Str` never needs to check again.
+/// It derefs to `str` for everything else.
+///
+/// # Examples
+///
+/// ## Basic example
+///
+/// ```rust
+/// use fd_bs58::Base58Str;
+///
+/// let key = Base58Str::new("XkCriyrNwS3G4rzAXtG5B1nnvb5Ka1JtCku93VqeKAr")?;
+/// assert_eq!(43, key.len());
+/// assert_eq!([7, 224, 70, 147], key.to_bytes_32()?[..4]);
+/// assert!(Base58Str::new("0OIl").is_err());
+/// # Ok::<(), fd_bs58::Error>(())
+/// ```
+#[derive(PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[repr(transparent)]
+pub struct Base58Str(str);
+
+/// Fails on the first character of `s` that isn't in [`BASE58_INVERSE`]
+fn validate(s: &str) -> Result<(), Error> {
+    let bytes = s.as_bytes();
+    for (index, &c) in bytes.iter().enumerate() {
+        let valid = c >= BASE58_INVERSE_TABLE_OFFSET
+            && c - BASE58_INVERSE_TABLE_OFFSET < BASE58_INVERSE_TABLE_SENTINEL
+            && BASE58_INVERSE[(c - BASE58_INVERSE_TABLE_OFFSET) as usize] != BASE58_INVALID_CHAR;
+        if !valid {
+            return Err(Error::invalid_character(bytes, index));
+        }
+    }
+    Ok(())
+}
+
+impl Base58Str {
+    /// Checks that every character of `s` is in the Bitcoin base58 alphabet.
+    ///
+    /// # Errors
+    ///
+    /// [`Error::InvalidCharacter`] for the first character that isn't.
+    pub fn new(s: &str) -> Result<&Base58Str, Error> {
+        validate(s)?;
+        Ok(Base58Str::from_str_unchecked(s))
+    }
+
+    fn from_str_unchecked(s: &str) -> &Base58Str {
+        // SAFETY: Base58Str is a repr(transparent) wrapper around str
+        unsafe { &*(s as *const str as *const Base58Str) }
+    }
+
+    /// Returns the underlying string slice.
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+
+    /// An upper bound on the number of bytes this decodes to.  Every leading `'1'` is one
+    /// zero byte, and the rest of the characters hold about 0.732 bytes each.
+    ///
+    /// # Examples
+    ///
+    /// ## Basic example
+    ///
+    /// ```rust
+    /// use fd_bs58::Base58Str;
+    ///
+    /// assert_eq!(3, Base58Str::new("111")?.decoded_len_hint());
+    /// let key = Base58Str::new("XkCriyrNwS3G4rzAXtG5B1nnvb5Ka1JtCku93VqeKAr")?;
+    /// assert!(key.decoded_len_hint() >= 32);
+    /// # Ok::<(), fd_bs58::Error>(())
+    /// ```
+    pub fn decoded_len_hint(&self) -> usize {
+        let leading_1s = self.0.bytes().take_while(|&c| c == b'1').count();
+        /* log(58) / log(256) < 0.733 */
+        leading_1s + ((self.0.len() - leading_1s) * 733).div_ceil(1000)
+    }
+
+    /// Decodes the 32 bytes this encodes, with the same SIMD code as
+    /// [`decode_32`](crate::decode_32()) when the CPU supports it.  The scalar code doesn't
+    /// check the characters again.
+    ///
+    /// # Errors
+    ///
+    /// The same length and size errors as [`decode_32`](crate::decode_32()), never
+    /// [`Error::InvalidCharacter`].
+    pub fn to_bytes_32(&self) -> Result<[u8; BYTE_COUNT_32], Error> {
+        decode_fixed_validated(self.0.as_bytes())
+    }
+
+    /// Decodes the 64 bytes this encodes, with the same SIMD code as
+    /// [`decode_64`](crate::decode_64()) when the CPU supports it.  The scalar code doesn't
+    /// check the characters again.
+    ///
+    /// # Errors
+    ///
+    /// The same length and size errors as [`decode_64`](crate::decode_64()), never
+    /// [`Error::InvalidCharacter`].
+    pub fn to_bytes_64(&self) -> Result<[u8; BYTE_COUNT_64], Error> {
+        decode_fixed_validated(self.0.as_bytes())
+    }
+}
+
+impl Deref for Base58Str {
+    type Target = str;
+
+    fn deref(&self) -> &str {
+        &self.0
+    }
+}
+
+impl AsRef<str> for Base58Str {
+    fn as_ref(&self) -> &str {
+        &self.0
+    }
+}
+
+impl AsRef<[u8]> for Base58Str {
+    fn as_ref(&self) -> &[u8] {
+        self.0.as_bytes()
+    }
+}
+
+impl Borrow<str> for Base58Str {
+    fn borrow(&self) -> &str {
+        &self.0
+    }
+}
+
+impl Default for &Base58Str {
+    fn default() -> Self {
+        Base58Str::from_str_unchecked("")
+    }
+}
+
+impl<'a> TryFrom<&'a str> for &'a Base58Str {
+    type Error = Error;
+
+    fn try_from(s: &'a str) -> Result<Self, Error> {
+        Base58Str::new(s)
+    }
+}
+
+impl PartialEq<str> for Base58Str {
+    fn eq(&self, other: &str) -> bool {
+        &self.0 == other
+    }
+}
+
+impl PartialEq<&str> for Base58Str {
+    fn eq(&self, other: &&str) -> bool {
+        &self.0 == *other
+    }
+}
+
+impl PartialEq<Base58Str> for str {
+    fn eq(&self, other: &Base58Str) -> bool {
+        self == &other.0
+    }
+}
+
+impl PartialEq<Base58Str> for &str {
+    fn eq(&self, other: &Base58Str) -> bool {
+        *self == &other.0
+    }
+}
+
+impl fmt::Display for Base58Str {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Display::fmt(&self.0, f)
+    }
+}
+
+impl fmt::Debug for Base58Str {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Debug::fmt(&self.0, f)
+    }
+}
+
+/// The owned version of [`Base58Str`], a [`String`] that only contains characters of the
+/// Bitcoin base58 alphabet.
+///
+/// # Examples
+///
+/// ## Basic example
+///
+/// ```rust
+/// use fd_bs58::{Base58Str, Base58String};
+///
+/// let key: Base58String = "XkCriyrNwS3G4rzAXtG5B1nnvb5Ka1JtCku93VqeKAr".parse()?;
+/// let borrowed: &Base58Str = &key;
+/// assert_eq!(borrowed.to_owned(), key);
+/// assert!(Base58String::try_from(String::from("0x1234")).is_err());
+/// # Ok::<(), fd_bs58::Error>(())
+/// ```
+#[cfg(feature = "alloc")]
+#[derive(Clone, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Base58String(String);
+
+#[cfg(feature = "alloc")]
+impl Base58String {
+    /// Returns the underlying string slice.
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+
+    /// Returns the borrowed version.
+    pub fn as_base58_str(&self) -> &Base58Str {
+        Base58Str::from_str_unchecked(&self.0)
+    }
+
+    /// Returns the underlying `String`.
+    pub fn into_string(self) -> String {
+        self.0
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl Deref for Base58String {
+    type Target = Base58Str;
+
+    fn deref(&self) -> &Base58Str {
+        self.as_base58_str()
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl AsRef<Base58Str> for Base58String {
+    fn as_ref(&self) -> &Base58Str {
+        self.as_base58_str()
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl AsRef<str> for Base58String {
+    fn as_ref(&self) -> &str {
+        &self.0
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl AsRef<[u8]> for Base58String {
+    fn as_ref(&self) -> &[u8] {
+        self.0.as_bytes()
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl Borrow<Base58Str> for Base58String {
+    fn borrow(&self) -> &Base58Str {
+        self.as_base58_str()
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl Borrow<str> for Base58String {
+    fn borrow(&self) -> &str {
+        &self.0
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl ToOwned for Base58Str {
+    type Owned = Base58String;
+
+    fn to_owned(&self) -> Base58String {
+        Base58String(self.0.to_owned())
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl From<&Base58Str> for Base58String {
+    fn from(s: &Base58Str) -> Self {
+        s.to_owned()
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl From<Base58String> for String {
+    fn from(s: Base58String) -> Self {
+        s.0
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl From<Base58String> for Box<str> {
+    fn from(s: Base58String) -> Self {
+        s.0.into_boxed_str()
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl FromStr for Base58String {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Error> {
+        Base58Str::new(s).map(ToOwned::to_owned)
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl TryFrom<&str> for Base58String {
+    type Error = Error;
+
+    fn try_from(s: &str) -> Result<Self, Error> {
+        s.parse()
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl TryFrom<String> for Base58String {
+    type Error = Error;
+
+    /// Checks `s` like [`Base58Str::new`], reusing its allocation.
+    fn try_from(s: String) -> Result<Self, Error> {
+        validate(&s)?;
+        Ok(Base58String(s))
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl PartialEq<str> for Base58String {
+    fn eq(&self, other: &str) -> bool {
+        self.0 == other
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl PartialEq<&str> for Base58String {
+    fn eq(&self, other: &&str) -> bool {
+        self.0 == *other
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl PartialEq<Base58Str> for Base58String {
+    fn eq(&self, other: &Base58Str) -> bool {
+        self.0 == other.0
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl PartialEq<Base58String> for Base58Str {
+    fn eq(&self, other: &Base58String) -> bool {
+        self.0 == other.0
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl fmt::Display for Base58String {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Display::fmt(&self.0, f)
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl fmt::Debug for Base58String {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Debug::fmt(&self.0, f)
+    }
+}
+
+/// A base58 string of at most `N` characters stored inline, as a length byte and an
+/// `[u8; N]`, so it is `Copy` and needs no allocation. [`encode_32_inline`] returns a
+/// `Base58Inline<44>` and [`encode_64_inline`] a `Base58Inline<88>`, which fit any encoding
+/// of their width.
+///
+/// It compares, orders and hashes like the `str` it derefs to, so it can be looked up by
+/// `&str` in a `HashMap`. With the `serde` feature it serializes as that string.
+///
+/// [`encode_32_inline`]: crate::encode_32_inline
+/// [`encode_64_inline`]: crate::encode_64_inline
+///
+/// # Examples
+///
+/// ## Basic example
+///
+/// ```rust
+/// use std::collections::HashMap;
+///
+/// use fd_bs58::Base58Inline;
+///
+/// let key: Base58Inline<44> = fd_bs58::encode_32_inline(&[0; 32]);
+/// assert_eq!("1".repeat(32), *key);
+///
+/// let mut balances = HashMap::new();
+/// balances.insert(key, 10);
+/// assert_eq!(Some(&10), balances.get("11111111111111111111111111111111"));
+/// ```
+#[derive(Clone, Copy)]
+pub struct Base58Inline<const N: usize> {
+    len: u8,
+    bytes: [u8; N],
+}
+
+impl<const N: usize> Base58Inline<N> {
+    const VALID: () = assert!(N <= u8::MAX as usize, "the length must fit in a byte");
+
+    /// Checks `s` like [`Base58Str::new`] and copies it inline.
+    ///
+    /// # Errors
+    ///
+    /// [`Error::InputTooLong`] if `s` is longer than `N`, and [`Error::InvalidCharacter`]
+    /// for the first character that isn't in the alphabet.
+    pub fn new(s: &str) -> Result<Self, Error> {
+        if s.len() > N {
+            return Err(Error::InputTooLong {
+                max: N,
+                actual: s.len(),
+            });
+        }
+        validate(s)?;
+        let mut bytes = [0; N];
+        bytes[..s.len()].copy_from_slice(s.as_bytes());
+        Ok(Base58Inline::from_encoded(bytes, s.len()))
+    }
+
+    /// Wraps the first `len` bytes of `bytes`, which an encoder just wrote
+    pub(crate) fn from_encoded(bytes: [u8; N], len: usize) -> Self {
+        let () = Self::VALID;
+        debug_assert!(validate(core::str::from_utf8(&bytes[..len]).unwrap()).is_ok());
+        Base58Inline {
+            len: len as u8,
+            bytes,
+        }
+    }
+
+    /// Returns the string.
+    pub fn as_str(&self) -> &str {
+        // SAFETY: the bytes were either written by an encoder or checked by `new`, so they
+        // are all from the ASCII base58 alphabet
+        unsafe { core::str::from_utf8_unchecked(&self.bytes[..self.len as usize]) }
+    }
+
+    /// Returns the string as a [`Base58Str`].
+    pub fn as_base58_str(&self) -> &Base58Str {
+        Base58Str::from_str_unchecked(self.as_str())
+    }
+}
+
+impl<const N: usize> Deref for Base58Inline<N> {
+    type Target = str;
+
+    fn deref(&self) -> &str {
+        self.as_str()
+    }
+}
+
+impl<const N: usize> AsRef<str> for Base58Inline<N> {
+    fn as_ref(&self) -> &str {
+        self.as_str()
+    }
+}
+
+impl<const N: usize> AsRef<[u8]> for Base58Inline<N> {
+    fn as_ref(&self) -> &[u8] {
+        self.as_str().as_bytes()
+    }
+}
+
+impl<const N: usize> AsRef<Base58Str> for Base58Inline<N> {
+    fn as_ref(&self) -> &Base58Str {
+        self.as_base58_str()
+    }
+}
+
+impl<const N: usize> Borrow<str> for Base58Inline<N> {
+    fn borrow(&self) -> &str {
+        self.as_str()
+    }
+}
+
+impl<const N: usize> Default for Base58Inline<N> {
+    fn default() -> Self {
+        Base58Inline::from_encoded([0; N], 0)
+    }
+}
+
+impl<const N: usize> PartialEq for Base58Inline<N> {
+    fn eq(&self, other: &Self) -> bool {
+        self.as_str() == other.as_str()
+    }
+}
+
+impl<const N: usize> Eq for Base58Inline<N> {}
+
+impl<const N: usize> PartialOrd for Base58Inline<N> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl<const N: usize> Ord for Base58Inline<N> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.as_str().cmp(other.as_str())
+    }
+}
+
+/* Has to match str for Borrow<str> */
+impl<const N: usize> Hash for Base58Inline<N> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.as_str().hash(state)
+    }
+}
+
+impl<const N: usize> PartialEq<str> for Base58Inline<N> {
+    fn eq(&self, other: &str) -> bool {
+        self.as_str() == other
+    }
+}
+
+impl<const N: usize> PartialEq<&str> for Base58Inline<N> {
+    fn eq(&self, other: &&str) -> bool {
+        self.as_str() == *other
+    }
+}
+
+impl<const N: usize> PartialEq<Base58Inline<N>> for str {
+    fn eq(&self, other: &Base58Inline<N>) -> bool {
+        self == other.as_str()
+    }
+}
+
+impl<const N: usize> PartialEq<Base58Inline<N>> for &str {
+    fn eq(&self, other: &Base58Inline<N>) -> bool {
+        *self == other.as_str()
+    }
+}
+
+impl<const N: usize> FromStr for Base58Inline<N> {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Error> {
+        Base58Inline::new(s)
+    }
+}
+
+impl<const N: usize> TryFrom<&str> for Base58Inline<N> {
+    type Error = Error;
+
+    fn try_from(s: &str) -> Result<Self, Error> {
+        Base58Inline::new(s)
+    }
+}
+
+impl<const N: usize> fmt::Display for Base58Inline<N> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Display::fmt(self.as_str(), f)
+    }
+}
+
+impl<const N: usize> fmt::Debug for Base58Inline<N> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Debug::fmt(self.as_str(), f)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{constants::BASE58_ALPHABET, Error};
+
+    #[cfg(feature = "alloc")]
+    use super::Base58String;
+    use super::{Base58Inline, Base58Str};
+
+    const KEY: &str = "XkCriyrNwS3G4rzAXtG5B1nnvb5Ka1JtCku93VqeKAr";
+
+    #[test]
+    fn test_validation() {
+        let key = Base58Str::new(KEY).unwrap();
+        assert_eq!(KEY, key.as_str());
+        assert_eq!(*key, *KEY);
+        assert_eq!(key, KEY);
+        assert_eq!(KEY, *key);
+        assert_eq!("", <&Base58Str>::default());
+        assert!(Base58Str::new(core::str::from_utf8(BASE58_ALPHABET).unwrap()).is_ok());
+
+        for (input, character, index) in [
+            ("0", '0', 0),
+            ("1O", 'O', 1),
+            ("11I", 'I', 2),
+            ("abcl", 'l', 3),
+            ("z ", ' ', 1),
+            ("1{", '{', 1),
+            ("é", 'é', 0),
+            ("1\0", '\0', 1),
+        ] {
+            let expected = Some(Error::InvalidCharacter { character, index });
+            assert_eq!(expected, Base58Str::new(input).err());
+            #[cfg(feature = "alloc")]
+            {
+                assert_eq!(expected, input.parse::<Base58String>().err());
+                assert_eq!(expected, Base58String::try_from(input.to_string()).err());
+            }
+        }
+    }
+
+    #[test]
+    fn test_decoding() {
+        let key = Base58Str::new(KEY).unwrap();
+        assert_eq!(crate::decode_32(KEY), key.to_bytes_32());
+        assert_eq!(
+            Err(Error::InputTooShort {
+                min: 32,
+                actual: 31
+            }),
+            Base58Str::new(&"1".repeat(31)).unwrap().to_bytes_32()
+        );
+        assert_eq!(
+            Err(Error::InvalidByteAmount),
+            Base58Str::new(&"z".repeat(44)).unwrap().to_bytes_32()
+        );
+
+        let mut signature = [0; 88];
+        let len = crate::encode_64_into(&[255; 64], &mut signature);
+        let signature = Base58Str::new(core::str::from_utf8(&signature[..len]).unwrap()).unwrap();
+        assert_eq!(Ok([255; 64]), signature.to_bytes_64());
+        assert_eq!(crate::decode_32(signature), signature.to_bytes_32());
+
+        for (input, len) in [("", 0), ("1", 1), ("2", 1), ("11z", 3), (KEY, 32)] {
+            let input = Base58Str::new(input).unwrap();
+            assert!((len..=len + 1).contains(&input.decoded_len_hint()));
+        }
+        assert!((64..=65).contains(&signature.decoded_len_hint()));
+    }
+
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn test_owned() {
+        let key: Base58String = KEY.parse().unwrap();
+        let borrowed: &Base58Str = &key;
+        assert_eq!(key, *borrowed);
+        assert_eq!(*borrowed, key);
+        assert_eq!(key, KEY);
+        assert_eq!(key, *KEY);
+        assert_eq!(KEY, key.to_string());
+        assert_eq!(format!("{KEY:?}"), format!("{key:?}"));
+        assert_eq!(format!("{KEY:?}"), format!("{borrowed:?}"));
+        assert_eq!(format!("{KEY:>50}"), format!("{borrowed:>50}"));
+        assert_eq!(KEY, String::from(key.clone()));
+
+        let mut set = std::collections::HashSet::new();
+        set.insert(key.clone());
+        assert!(set.contains(borrowed));
+        assert!(set.contains(KEY));
+        assert_eq!(Ok(key), Base58String::try_from(KEY.to_string()));
+    }
+
+    #[test]
+    fn test_inline() {
+        let key = crate::encode_32_inline(&crate::decode_32(KEY).unwrap());
+        assert_eq!(KEY, key.as_str());
+        assert_eq!(key, KEY);
+        assert_eq!(KEY, key);
+        assert_eq!(*KEY, *key);
+        assert_eq!(KEY, key.as_base58_str());
+        assert_eq!(Ok(key), KEY.parse());
+        assert_eq!(format!("{KEY:?}"), format!("{key:?}"));
+        assert_eq!(format!("{KEY:>50}"), format!("{key:>50}"));
+
+        let copy = key;
+        assert_eq!(key, copy);
+        let low = crate::encode_32_inline(&[0; 32]);
+        assert!(low < key);
+        assert_eq!("1".repeat(32), *low);
+        assert_eq!("", Base58Inline::<44>::default().as_str());
+
+        let signature = crate::encode_64_inline(&[255; 64]);
+        assert_eq!(Ok([255; 64]), crate::decode_64(&*signature));
+        assert_eq!(88, signature.len());
+
+        let mut set = std::collections::HashSet::new();
+        set.insert(key);
+        assert!(set.contains(KEY));
+        assert!(!set.contains(&*low));
+
+        assert_eq!(
+            Err(Error::InputTooLong {
+                max: 44,
+                actual: 45
+            }),
+            "1".repeat(45).parse::<Base58Inline<44>>()
+        );
+        assert_eq!(
+            Err(Error::InvalidCharacter {
+                character: '0',
+                index: 1
+            }),
+            Base58Inline::<44>::try_from("10")
+        );
+    }
+}